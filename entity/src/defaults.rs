@@ -34,6 +34,61 @@ impl Linked for PostsForTag {
     }
 }
 
+mod builds {
+    use crate::{
+        builds::{ActiveModel, Model},
+        enums::BuildStatus,
+    };
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                status: Set(BuildStatus::Queued.to_string()),
+                changes: Set(1),
+                run_after: Set(now),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                status: BuildStatus::Queued.to_string(),
+                reason: String::from(""),
+                changes: 1,
+                log: None,
+                triggered_by: None,
+                run_after: now,
+                started_at: None,
+                finished_at: None,
+                created_at: now,
+                updated_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn triggered_by(&self) -> Option<[u8; 16]> {
+            self.triggered_by.as_deref().map(super::get_uuid_bytes)
+        }
+    }
+}
+
 mod users {
     use crate::{
         enums::Role,
@@ -68,12 +123,20 @@ mod users {
                 role: Role::Writer.to_string(),
                 created_at: now,
                 updated_at: now,
+                display_name: None,
+                handle: None,
+                bio: None,
+                avatar_uuid: None,
             }
         }
 
         pub fn uuid(&self) -> [u8; 16] {
             super::get_uuid_bytes(&self.uuid)
         }
+
+        pub fn avatar_uuid(&self) -> Option<[u8; 16]> {
+            self.avatar_uuid.as_deref().map(super::get_uuid_bytes)
+        }
     }
 }
 
@@ -114,6 +177,12 @@ mod posts {
                 raw: String::from(""),
                 created_at: now,
                 updated_at: now,
+                slug: String::from(""),
+                publish_at: None,
+                published_at: None,
+                excerpt: None,
+                locale: String::from("en"),
+                translation_group: None,
             }
         }
 
@@ -124,6 +193,10 @@ mod posts {
         pub fn created_by(&self) -> [u8; 16] {
             super::get_uuid_bytes(&self.created_by)
         }
+
+        pub fn translation_group(&self) -> Option<[u8; 16]> {
+            self.translation_group.as_deref().map(super::get_uuid_bytes)
+        }
     }
 }
 
@@ -154,6 +227,10 @@ mod tags {
             Model {
                 uuid: uuid.as_bytes().to_vec(),
                 name: String::from(""),
+                normalized_name: String::from(""),
+                slug: String::from(""),
+                description: None,
+                parent_uuid: None,
                 created_at: now,
                 updated_at: now,
             }
@@ -162,6 +239,10 @@ mod tags {
         pub fn uuid(&self) -> [u8; 16] {
             super::get_uuid_bytes(&self.uuid)
         }
+
+        pub fn parent_uuid(&self) -> Option<[u8; 16]> {
+            self.parent_uuid.as_deref().map(super::get_uuid_bytes)
+        }
     }
 }
 
@@ -197,6 +278,317 @@ mod post_tags {
     }
 }
 
+mod tag_synonyms {
+    use crate::tag_synonyms::{ActiveModel, Model};
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                created_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                alias: String::from(""),
+                tag_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                created_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn tag_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.tag_uuid)
+        }
+    }
+}
+
+mod series {
+    use crate::series::{ActiveModel, Model};
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                slug: String::from(""),
+                title: String::from(""),
+                description: None,
+                created_by: Uuid::new_v4().as_bytes().to_vec(),
+                created_at: now,
+                updated_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn created_by(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.created_by)
+        }
+    }
+}
+
+mod series_posts {
+    use crate::series_posts::{ActiveModel, Model};
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                series_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                post_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                position: 0,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn series_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.series_uuid)
+        }
+
+        pub fn post_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.post_uuid)
+        }
+    }
+}
+
+mod related_posts {
+    use crate::related_posts::{ActiveModel, Model};
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                created_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                post_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                related_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                score: 0.0,
+                created_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn post_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.post_uuid)
+        }
+
+        pub fn related_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.related_uuid)
+        }
+    }
+}
+
+mod post_authors {
+    use crate::{
+        enums::CreditRole,
+        post_authors::{ActiveModel, Model},
+    };
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                created_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                post_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                user_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                role: CreditRole::Author.to_string(),
+                position: 0,
+                created_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn post_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.post_uuid)
+        }
+
+        pub fn user_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.user_uuid)
+        }
+    }
+}
+
+mod post_redirects {
+    use crate::post_redirects::{ActiveModel, Model};
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                created_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                post_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                slug: String::from(""),
+                created_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn post_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.post_uuid)
+        }
+    }
+}
+
+mod post_revisions {
+    use crate::post_revisions::{ActiveModel, Model};
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                created_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                post_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                title: String::from(""),
+                raw: String::from(""),
+                created_by: Uuid::new_v4().as_bytes().to_vec(),
+                created_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn post_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.post_uuid)
+        }
+
+        pub fn created_by(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.created_by)
+        }
+    }
+}
+
 mod uploads {
     use crate::uploads::{ActiveModel, Model};
     use sea_orm::entity::prelude::*;
@@ -239,6 +631,151 @@ mod uploads {
     }
 }
 
+mod webhooks {
+    use crate::webhooks::{ActiveModel, Model};
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                active: Set(true),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                url: String::from(""),
+                secret: String::from(""),
+                events: String::from(""),
+                active: true,
+                created_by: Uuid::new_v4().as_bytes().to_vec(),
+                created_at: now,
+                updated_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn created_by(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.created_by)
+        }
+    }
+}
+
+mod webhook_deliveries {
+    use crate::{
+        enums::DeliveryStatus,
+        webhook_deliveries::{ActiveModel, Model},
+    };
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                status: Set(DeliveryStatus::Pending.to_string()),
+                attempts: Set(0),
+                next_attempt_at: Set(Some(now)),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                webhook_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                event: String::from(""),
+                payload: String::from(""),
+                status: DeliveryStatus::Pending.to_string(),
+                attempts: 0,
+                response_status: None,
+                error: None,
+                next_attempt_at: Some(now),
+                created_at: now,
+                updated_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn webhook_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.webhook_uuid)
+        }
+    }
+}
+
+mod user_links {
+    use crate::user_links::{ActiveModel, Model};
+    use sea_orm::entity::prelude::*;
+    use sea_orm::ActiveValue::Set;
+    use uuid::Uuid;
+
+    impl ActiveModelBehavior for ActiveModel {
+        fn new() -> Self {
+            let now = super::get_now();
+            let uuid = Uuid::new_v4().as_bytes().to_vec();
+
+            Self {
+                uuid: Set(uuid),
+                created_at: Set(now),
+                ..ActiveModelTrait::default()
+            }
+        }
+    }
+
+    impl Model {
+        pub fn default(uuid: Uuid) -> Self {
+            let now = super::get_now();
+
+            Model {
+                uuid: uuid.as_bytes().to_vec(),
+                user_uuid: Uuid::new_v4().as_bytes().to_vec(),
+                label: String::from(""),
+                url: String::from(""),
+                position: 0,
+                created_at: now,
+            }
+        }
+
+        pub fn uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.uuid)
+        }
+
+        pub fn user_uuid(&self) -> [u8; 16] {
+            super::get_uuid_bytes(&self.user_uuid)
+        }
+    }
+}
+
 fn get_now() -> DateTime<Utc> {
     Utc::now()
 }
@@ -12,4 +12,36 @@ pub enum Status {
     Published,
     Disabled,
     Draft,
+    Scheduled,
+}
+
+#[derive(Display, Debug, Clone, PartialEq, EnumString, AsRefStr)]
+pub enum WebhookEvent {
+    PostCreated,
+    PostUpdated,
+    PostStatusChanged,
+    PostDeleted,
+}
+
+#[derive(Display, Debug, Clone, PartialEq, EnumString, AsRefStr)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+#[derive(Display, Debug, Clone, PartialEq, EnumString, AsRefStr)]
+pub enum BuildStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Display, Debug, Clone, PartialEq, EnumString, AsRefStr)]
+pub enum CreditRole {
+    Author,
+    CoAuthor,
+    Editor,
+    Reviewer,
 }
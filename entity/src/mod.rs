@@ -2,9 +2,20 @@
 
 pub mod prelude;
 
+pub mod builds;
+pub mod post_authors;
+pub mod post_redirects;
+pub mod post_revisions;
 pub mod post_tags;
 pub mod posts;
+pub mod related_posts;
 pub mod seaql_migrations;
+pub mod series;
+pub mod series_posts;
+pub mod tag_synonyms;
 pub mod tags;
 pub mod uploads;
+pub mod user_links;
 pub mod users;
+pub mod webhook_deliveries;
+pub mod webhooks;
@@ -14,6 +14,12 @@ pub struct Model {
     pub created_by: Vec<u8>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
+    pub slug: String,
+    pub publish_at: Option<DateTimeUtc>,
+    pub published_at: Option<DateTimeUtc>,
+    pub excerpt: Option<String>,
+    pub locale: String,
+    pub translation_group: Option<Vec<u8>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -26,8 +32,16 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(has_many = "super::post_authors::Entity")]
+    PostAuthors,
+    #[sea_orm(has_many = "super::post_redirects::Entity")]
+    PostRedirects,
+    #[sea_orm(has_many = "super::post_revisions::Entity")]
+    PostRevisions,
     #[sea_orm(has_many = "super::post_tags::Entity")]
     PostTags,
+    #[sea_orm(has_many = "super::series_posts::Entity")]
+    SeriesPosts,
 }
 
 impl Related<super::users::Entity> for Entity {
@@ -36,8 +50,32 @@ impl Related<super::users::Entity> for Entity {
     }
 }
 
+impl Related<super::post_authors::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostAuthors.def()
+    }
+}
+
+impl Related<super::post_redirects::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostRedirects.def()
+    }
+}
+
+impl Related<super::post_revisions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostRevisions.def()
+    }
+}
+
 impl Related<super::post_tags::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::PostTags.def()
     }
 }
+
+impl Related<super::series_posts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SeriesPosts.def()
+    }
+}
@@ -1,8 +1,19 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.8.0
 
+pub use super::builds::Entity as Builds;
+pub use super::post_authors::Entity as PostAuthors;
+pub use super::post_redirects::Entity as PostRedirects;
+pub use super::post_revisions::Entity as PostRevisions;
 pub use super::post_tags::Entity as PostTags;
 pub use super::posts::Entity as Posts;
+pub use super::related_posts::Entity as RelatedPosts;
 pub use super::seaql_migrations::Entity as SeaqlMigrations;
+pub use super::series::Entity as Series;
+pub use super::series_posts::Entity as SeriesPosts;
+pub use super::tag_synonyms::Entity as TagSynonyms;
 pub use super::tags::Entity as Tags;
 pub use super::uploads::Entity as Uploads;
+pub use super::user_links::Entity as UserLinks;
 pub use super::users::Entity as Users;
+pub use super::webhook_deliveries::Entity as WebhookDeliveries;
+pub use super::webhooks::Entity as Webhooks;
@@ -8,14 +8,28 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub uuid: Vec<u8>,
     pub name: String,
+    pub normalized_name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    pub parent_uuid: Option<Vec<u8>>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentUuid",
+        to = "Column::Uuid",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    SelfRef,
     #[sea_orm(has_many = "super::post_tags::Entity")]
     PostTags,
+    #[sea_orm(has_many = "super::tag_synonyms::Entity")]
+    TagSynonyms,
 }
 
 impl Related<super::post_tags::Entity> for Entity {
@@ -23,3 +37,9 @@ impl Related<super::post_tags::Entity> for Entity {
         Relation::PostTags.def()
     }
 }
+
+impl Related<super::tag_synonyms::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TagSynonyms.def()
+    }
+}
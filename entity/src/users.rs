@@ -12,14 +12,56 @@ pub struct Model {
     pub role: String,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
+    pub display_name: Option<String>,
+    pub handle: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_uuid: Option<Vec<u8>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::uploads::Entity",
+        from = "Column::AvatarUuid",
+        to = "super::uploads::Column::Uuid",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    Avatar,
+    #[sea_orm(has_many = "super::builds::Entity")]
+    Builds,
+    #[sea_orm(has_many = "super::post_authors::Entity")]
+    PostAuthors,
+    #[sea_orm(has_many = "super::post_revisions::Entity")]
+    PostRevisions,
     #[sea_orm(has_many = "super::posts::Entity")]
     Posts,
+    #[sea_orm(has_many = "super::series::Entity")]
+    Series,
     #[sea_orm(has_many = "super::uploads::Entity")]
     Uploads,
+    #[sea_orm(has_many = "super::user_links::Entity")]
+    UserLinks,
+    #[sea_orm(has_many = "super::webhooks::Entity")]
+    Webhooks,
+}
+
+impl Related<super::builds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Builds.def()
+    }
+}
+
+impl Related<super::post_authors::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostAuthors.def()
+    }
+}
+
+impl Related<super::post_revisions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostRevisions.def()
+    }
 }
 
 impl Related<super::posts::Entity> for Entity {
@@ -28,8 +70,26 @@ impl Related<super::posts::Entity> for Entity {
     }
 }
 
+impl Related<super::series::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Series.def()
+    }
+}
+
 impl Related<super::uploads::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Uploads.def()
     }
 }
+
+impl Related<super::user_links::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserLinks.def()
+    }
+}
+
+impl Related<super::webhooks::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhooks.def()
+    }
+}
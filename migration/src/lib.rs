@@ -1,12 +1,50 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220710_000001_create_tables;
+mod m20220801_000001_add_post_slugs;
+mod m20220802_000001_create_post_redirects;
+mod m20220803_000001_create_post_revisions;
+mod m20220804_000001_add_post_scheduling;
+mod m20220805_000001_add_post_excerpts;
+mod m20220806_000001_create_webhooks;
+mod m20220807_000001_create_builds;
+mod m20220808_000001_create_posts_search;
+mod m20220809_000001_create_related_posts;
+mod m20220810_000001_create_series;
+mod m20220811_000001_create_post_authors;
+mod m20220812_000001_add_user_profiles;
+mod m20220813_000001_add_post_translations;
+mod m20220814_000001_add_post_tags_moved_trigger;
+mod m20220815_000001_add_tag_slugs;
+mod m20220816_000001_add_tag_parents;
+mod m20220817_000001_add_tag_synonyms;
+pub mod tag_duplicates;
+pub mod tag_names;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220710_000001_create_tables::Migration)]
+        vec![
+            Box::new(m20220710_000001_create_tables::Migration),
+            Box::new(m20220801_000001_add_post_slugs::Migration),
+            Box::new(m20220802_000001_create_post_redirects::Migration),
+            Box::new(m20220803_000001_create_post_revisions::Migration),
+            Box::new(m20220804_000001_add_post_scheduling::Migration),
+            Box::new(m20220805_000001_add_post_excerpts::Migration),
+            Box::new(m20220806_000001_create_webhooks::Migration),
+            Box::new(m20220807_000001_create_builds::Migration),
+            Box::new(m20220808_000001_create_posts_search::Migration),
+            Box::new(m20220809_000001_create_related_posts::Migration),
+            Box::new(m20220810_000001_create_series::Migration),
+            Box::new(m20220811_000001_create_post_authors::Migration),
+            Box::new(m20220812_000001_add_user_profiles::Migration),
+            Box::new(m20220813_000001_add_post_translations::Migration),
+            Box::new(m20220814_000001_add_post_tags_moved_trigger::Migration),
+            Box::new(m20220815_000001_add_tag_slugs::Migration),
+            Box::new(m20220816_000001_add_tag_parents::Migration),
+            Box::new(m20220817_000001_add_tag_synonyms::Migration),
+        ]
     }
 }
@@ -0,0 +1,98 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+use std::collections::HashSet;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220801_000001_add_post_slugs"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        ALTER TABLE `posts` ADD COLUMN slug TEXT NOT NULL DEFAULT '';
+        "#;
+        let slug = Statement::from_string(backend, sql.to_owned());
+        conn.execute(slug).await?;
+
+        // Existing posts need a slug before the unique index can be created.
+        let sql = r#"
+        SELECT uuid, title FROM `posts` ORDER BY created_at ASC;
+        "#;
+        let rows = conn
+            .query_all(Statement::from_string(backend, sql.to_owned()))
+            .await?;
+
+        let mut taken: HashSet<String> = HashSet::new();
+        for row in rows {
+            let uuid: Vec<u8> = row.try_get("", "uuid")?;
+            let title: String = row.try_get("", "title")?;
+
+            let base = slugify(&title);
+            let mut slug = base.clone();
+            let mut suffix = 2;
+            while taken.contains(&slug) {
+                slug = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+            taken.insert(slug.clone());
+
+            let update = Statement::from_sql_and_values(
+                backend,
+                "UPDATE `posts` SET slug = ? WHERE uuid = ?",
+                vec![slug.into(), uuid.into()],
+            );
+            conn.execute(update).await?;
+        }
+
+        let sql = r#"
+        CREATE UNIQUE INDEX `posts_slug_idx` ON `posts` (slug);
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+        conn.execute(index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP INDEX `posts_slug_idx`;
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `posts` DROP COLUMN slug;
+        "#;
+        let slug = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(index).await?;
+        conn.execute(slug).await?;
+
+        Ok(())
+    }
+}
+
+fn slugify(text: &str) -> String {
+    let slug = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+
+    match slug.as_str() {
+        "" => String::from("post"),
+        _ => slug,
+    }
+}
@@ -0,0 +1,51 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220802_000001_create_post_redirects"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        CREATE TABLE `post_redirects` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            post_uuid BLOB NOT NULL,
+            slug TEXT UNIQUE NOT NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            FOREIGN KEY (post_uuid)
+            REFERENCES posts (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let post_redirects = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(post_redirects).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `post_redirects`;
+        "#;
+        let post_redirects = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(post_redirects).await?;
+
+        Ok(())
+    }
+}
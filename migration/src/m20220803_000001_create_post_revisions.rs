@@ -0,0 +1,65 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220803_000001_create_post_revisions"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        CREATE TABLE `post_revisions` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            post_uuid BLOB NOT NULL,
+            title TEXT NOT NULL,
+            raw TEXT NOT NULL,
+            created_by BLOB NOT NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            FOREIGN KEY (post_uuid)
+            REFERENCES posts (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE,
+            FOREIGN KEY (created_by)
+            REFERENCES users (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let post_revisions = Statement::from_string(backend, sql.to_owned());
+
+        // Every existing post starts its history with the content it has right now.
+        let sql = r#"
+        INSERT INTO `post_revisions` (uuid, post_uuid, title, raw, created_by, created_at)
+        SELECT randomblob(16), uuid, title, raw, created_by, updated_at FROM `posts`;
+        "#;
+        let initial_revisions = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(post_revisions).await?;
+        conn.execute(initial_revisions).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `post_revisions`;
+        "#;
+        let post_revisions = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(post_revisions).await?;
+
+        Ok(())
+    }
+}
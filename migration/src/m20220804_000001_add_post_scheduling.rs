@@ -0,0 +1,80 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220804_000001_add_post_scheduling"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        ALTER TABLE `posts` ADD COLUMN publish_at TIMESTAMP NULL;
+        "#;
+        let publish_at = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `posts` ADD COLUMN published_at TIMESTAMP NULL;
+        "#;
+        let published_at = Statement::from_string(backend, sql.to_owned());
+
+        // There is no record of when the already published posts went live, the last update is
+        // the closest thing to it.
+        let sql = r#"
+        UPDATE `posts` SET published_at = updated_at WHERE status = 'Published';
+        "#;
+        let backfill = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `posts_status_publish_at_idx` ON `posts` (status, publish_at);
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(publish_at).await?;
+        conn.execute(published_at).await?;
+        conn.execute(backfill).await?;
+        conn.execute(index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP INDEX `posts_status_publish_at_idx`;
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        UPDATE `posts` SET status = 'Draft' WHERE status = 'Scheduled';
+        "#;
+        let scheduled = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `posts` DROP COLUMN published_at;
+        "#;
+        let published_at = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `posts` DROP COLUMN publish_at;
+        "#;
+        let publish_at = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(index).await?;
+        conn.execute(scheduled).await?;
+        conn.execute(published_at).await?;
+        conn.execute(publish_at).await?;
+
+        Ok(())
+    }
+}
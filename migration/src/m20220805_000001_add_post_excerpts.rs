@@ -0,0 +1,40 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220805_000001_add_post_excerpts"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        ALTER TABLE `posts` ADD COLUMN excerpt TEXT NULL;
+        "#;
+        let excerpt = Statement::from_string(backend, sql.to_owned());
+        conn.execute(excerpt).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        ALTER TABLE `posts` DROP COLUMN excerpt;
+        "#;
+        let excerpt = Statement::from_string(backend, sql.to_owned());
+        conn.execute(excerpt).await?;
+
+        Ok(())
+    }
+}
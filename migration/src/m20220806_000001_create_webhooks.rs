@@ -0,0 +1,90 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220806_000001_create_webhooks"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        CREATE TABLE `webhooks` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            url TEXT NOT NULL,
+            secret TEXT NOT NULL,
+            events TEXT NOT NULL,
+            active BOOLEAN NOT NULL DEFAULT 1,
+            created_by BLOB NOT NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            updated_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            FOREIGN KEY (created_by)
+            REFERENCES users (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let webhooks = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE TABLE `webhook_deliveries` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            webhook_uuid BLOB NOT NULL,
+            event TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            status TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            response_status INTEGER NULL,
+            error TEXT NULL,
+            next_attempt_at TIMESTAMP NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            updated_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            FOREIGN KEY (webhook_uuid)
+            REFERENCES webhooks (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let deliveries = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `webhook_deliveries_status_next_attempt_at_idx`
+            ON `webhook_deliveries` (status, next_attempt_at);
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(webhooks).await?;
+        conn.execute(deliveries).await?;
+        conn.execute(index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `webhook_deliveries`;
+        "#;
+        let deliveries = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        DROP TABLE `webhooks`;
+        "#;
+        let webhooks = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(deliveries).await?;
+        conn.execute(webhooks).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220807_000001_create_builds"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        CREATE TABLE `builds` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            status TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            changes INTEGER NOT NULL DEFAULT 1,
+            log TEXT NULL,
+            triggered_by BLOB NULL,
+            run_after TIMESTAMP NOT NULL,
+            started_at TIMESTAMP NULL,
+            finished_at TIMESTAMP NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            updated_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            FOREIGN KEY (triggered_by)
+            REFERENCES users (uuid)
+                ON DELETE SET NULL
+                ON UPDATE CASCADE
+        );
+        "#;
+        let builds = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `builds_status_run_after_idx` ON `builds` (status, run_after);
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+
+        // Changes collapse into the queued build, so there is never more than one waiting.
+        let sql = r#"
+        CREATE UNIQUE INDEX `builds_queued_idx` ON `builds` (status) WHERE status = 'Queued';
+        "#;
+        let queued_index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(builds).await?;
+        conn.execute(index).await?;
+        conn.execute(queued_index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `builds`;
+        "#;
+        let builds = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(builds).await?;
+
+        Ok(())
+    }
+}
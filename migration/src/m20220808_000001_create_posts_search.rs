@@ -0,0 +1,150 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220808_000001_create_posts_search"
+    }
+}
+
+/// Tag names of a post joined by spaces, `{post}` is replaced by the column holding its uuid.
+const POST_TAG_NAMES: &str = r#"
+    COALESCE((
+        SELECT group_concat(tags.name, ' ')
+        FROM post_tags
+        INNER JOIN tags ON tags.uuid = post_tags.tag_uuid
+        WHERE post_tags.post_uuid = {post}
+    ), '')
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        CREATE VIRTUAL TABLE `posts_search` USING fts5(
+            uuid UNINDEXED,
+            title,
+            raw,
+            tags,
+            tokenize = 'porter unicode61'
+        );
+        "#;
+        let search = Statement::from_string(backend, sql.to_owned());
+
+        let sql = format!(
+            r#"
+        INSERT INTO `posts_search` (uuid, title, raw, tags)
+        SELECT posts.uuid, posts.title, posts.raw, {}
+        FROM posts;
+        "#,
+            POST_TAG_NAMES.replace("{post}", "posts.uuid")
+        );
+        let backfill = Statement::from_string(backend, sql);
+
+        let sql = r#"
+        CREATE TRIGGER `posts_search_insert` AFTER INSERT ON `posts`
+        BEGIN
+            INSERT INTO `posts_search` (uuid, title, raw, tags)
+            VALUES (new.uuid, new.title, new.raw, '');
+        END;
+        "#;
+        let insert = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE TRIGGER `posts_search_update` AFTER UPDATE OF title, raw ON `posts`
+        BEGIN
+            UPDATE `posts_search` SET title = new.title, raw = new.raw
+            WHERE uuid = new.uuid;
+        END;
+        "#;
+        let update = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE TRIGGER `posts_search_delete` AFTER DELETE ON `posts`
+        BEGIN
+            DELETE FROM `posts_search` WHERE uuid = old.uuid;
+        END;
+        "#;
+        let delete = Statement::from_string(backend, sql.to_owned());
+
+        let sql = format!(
+            r#"
+        CREATE TRIGGER `posts_search_tag_added` AFTER INSERT ON `post_tags`
+        BEGIN
+            UPDATE `posts_search` SET tags = {}
+            WHERE uuid = new.post_uuid;
+        END;
+        "#,
+            POST_TAG_NAMES.replace("{post}", "new.post_uuid")
+        );
+        let tag_added = Statement::from_string(backend, sql);
+
+        let sql = format!(
+            r#"
+        CREATE TRIGGER `posts_search_tag_removed` AFTER DELETE ON `post_tags`
+        BEGIN
+            UPDATE `posts_search` SET tags = {}
+            WHERE uuid = old.post_uuid;
+        END;
+        "#,
+            POST_TAG_NAMES.replace("{post}", "old.post_uuid")
+        );
+        let tag_removed = Statement::from_string(backend, sql);
+
+        let sql = format!(
+            r#"
+        CREATE TRIGGER `posts_search_tag_renamed` AFTER UPDATE OF name ON `tags`
+        BEGIN
+            UPDATE `posts_search` SET tags = {}
+            WHERE uuid IN (SELECT post_uuid FROM post_tags WHERE tag_uuid = new.uuid);
+        END;
+        "#,
+            POST_TAG_NAMES.replace("{post}", "posts_search.uuid")
+        );
+        let tag_renamed = Statement::from_string(backend, sql);
+
+        conn.execute(search).await?;
+        conn.execute(backfill).await?;
+        conn.execute(insert).await?;
+        conn.execute(update).await?;
+        conn.execute(delete).await?;
+        conn.execute(tag_added).await?;
+        conn.execute(tag_removed).await?;
+        conn.execute(tag_renamed).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let triggers = [
+            "posts_search_insert",
+            "posts_search_update",
+            "posts_search_delete",
+            "posts_search_tag_added",
+            "posts_search_tag_removed",
+            "posts_search_tag_renamed",
+        ];
+
+        for trigger in triggers {
+            let sql = format!("DROP TRIGGER `{}`;", trigger);
+            conn.execute(Statement::from_string(backend, sql)).await?;
+        }
+
+        let sql = r#"
+        DROP TABLE `posts_search`;
+        "#;
+        let search = Statement::from_string(backend, sql.to_owned());
+        conn.execute(search).await?;
+
+        Ok(())
+    }
+}
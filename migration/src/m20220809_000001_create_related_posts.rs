@@ -0,0 +1,69 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220809_000001_create_related_posts"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        CREATE TABLE `related_posts` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            post_uuid BLOB NOT NULL,
+            related_uuid BLOB NOT NULL,
+            score REAL NOT NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            UNIQUE (post_uuid, related_uuid),
+            FOREIGN KEY (post_uuid)
+            REFERENCES posts (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE,
+            FOREIGN KEY (related_uuid)
+            REFERENCES posts (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let related_posts = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `related_posts_post_uuid_idx` ON `related_posts` (post_uuid);
+        "#;
+        let post_index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `related_posts_related_uuid_idx` ON `related_posts` (related_uuid);
+        "#;
+        let related_index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(related_posts).await?;
+        conn.execute(post_index).await?;
+        conn.execute(related_index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `related_posts`;
+        "#;
+        let related_posts = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(related_posts).await?;
+
+        Ok(())
+    }
+}
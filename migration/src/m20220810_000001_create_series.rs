@@ -0,0 +1,86 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220810_000001_create_series"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        CREATE TABLE `series` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            slug TEXT UNIQUE NOT NULL,
+            title TEXT NOT NULL,
+            description TEXT NULL,
+            created_by BLOB NOT NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            updated_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            FOREIGN KEY (created_by)
+            REFERENCES users (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let series = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE TABLE `series_posts` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            series_uuid BLOB NOT NULL,
+            post_uuid BLOB UNIQUE NOT NULL,
+            position INTEGER NOT NULL,
+            FOREIGN KEY (series_uuid)
+            REFERENCES series (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE,
+            FOREIGN KEY (post_uuid)
+            REFERENCES posts (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let series_posts = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `series_posts_series_uuid_position_idx`
+            ON `series_posts` (series_uuid, position);
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(series).await?;
+        conn.execute(series_posts).await?;
+        conn.execute(index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `series_posts`;
+        "#;
+        let series_posts = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        DROP TABLE `series`;
+        "#;
+        let series = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(series_posts).await?;
+        conn.execute(series).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220811_000001_create_post_authors"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        CREATE TABLE `post_authors` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            post_uuid BLOB NOT NULL,
+            user_uuid BLOB NOT NULL,
+            role TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            UNIQUE (post_uuid, user_uuid),
+            FOREIGN KEY (post_uuid)
+            REFERENCES posts (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE,
+            FOREIGN KEY (user_uuid)
+            REFERENCES users (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let post_authors = Statement::from_string(backend, sql.to_owned());
+
+        // Whoever created a post so far was its only author.
+        let sql = r#"
+        INSERT INTO `post_authors` (uuid, post_uuid, user_uuid, role, position)
+        SELECT randomblob(16), uuid, created_by, 'Author', 0
+        FROM posts;
+        "#;
+        let backfill = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(post_authors).await?;
+        conn.execute(backfill).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `post_authors`;
+        "#;
+        let post_authors = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(post_authors).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220812_000001_add_user_profiles"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        ALTER TABLE `users` ADD COLUMN display_name TEXT NULL;
+        "#;
+        let display_name = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `users` ADD COLUMN handle TEXT NULL;
+        "#;
+        let handle = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `users` ADD COLUMN bio TEXT NULL;
+        "#;
+        let bio = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `users` ADD COLUMN avatar_uuid BLOB NULL
+            REFERENCES uploads (uuid)
+                ON DELETE SET NULL
+                ON UPDATE CASCADE;
+        "#;
+        let avatar_uuid = Statement::from_string(backend, sql.to_owned());
+
+        // Handles are optional, SQLite lets any number of users go without one.
+        let sql = r#"
+        CREATE UNIQUE INDEX `users_handle_idx` ON `users` (handle);
+        "#;
+        let handle_index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE TABLE `user_links` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            user_uuid BLOB NOT NULL,
+            label TEXT NOT NULL,
+            url TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            FOREIGN KEY (user_uuid)
+            REFERENCES users (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let user_links = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `user_links_user_uuid_idx` ON `user_links` (user_uuid, position);
+        "#;
+        let user_links_index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(display_name).await?;
+        conn.execute(handle).await?;
+        conn.execute(bio).await?;
+        conn.execute(avatar_uuid).await?;
+        conn.execute(handle_index).await?;
+        conn.execute(user_links).await?;
+        conn.execute(user_links_index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `user_links`;
+        "#;
+        let user_links = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        DROP INDEX `users_handle_idx`;
+        "#;
+        let handle_index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `users` DROP COLUMN avatar_uuid;
+        "#;
+        let avatar_uuid = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `users` DROP COLUMN bio;
+        "#;
+        let bio = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `users` DROP COLUMN handle;
+        "#;
+        let handle = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `users` DROP COLUMN display_name;
+        "#;
+        let display_name = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(user_links).await?;
+        conn.execute(handle_index).await?;
+        conn.execute(avatar_uuid).await?;
+        conn.execute(bio).await?;
+        conn.execute(handle).await?;
+        conn.execute(display_name).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220813_000001_add_post_translations"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        // Everything written so far was in English.
+        let sql = r#"
+        ALTER TABLE `posts` ADD COLUMN locale TEXT NOT NULL DEFAULT 'en';
+        "#;
+        let locale = Statement::from_string(backend, sql.to_owned());
+
+        // Translations of the same post share a group, posts without translations have none.
+        let sql = r#"
+        ALTER TABLE `posts` ADD COLUMN translation_group BLOB NULL;
+        "#;
+        let translation_group = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE UNIQUE INDEX `posts_translation_group_locale_idx`
+        ON `posts` (translation_group, locale);
+        "#;
+        let translation_index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `posts_locale_idx` ON `posts` (locale);
+        "#;
+        let locale_index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(locale).await?;
+        conn.execute(translation_group).await?;
+        conn.execute(translation_index).await?;
+        conn.execute(locale_index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP INDEX `posts_locale_idx`;
+        "#;
+        let locale_index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        DROP INDEX `posts_translation_group_locale_idx`;
+        "#;
+        let translation_index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `posts` DROP COLUMN translation_group;
+        "#;
+        let translation_group = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `posts` DROP COLUMN locale;
+        "#;
+        let locale = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(locale_index).await?;
+        conn.execute(translation_index).await?;
+        conn.execute(translation_group).await?;
+        conn.execute(locale).await?;
+
+        Ok(())
+    }
+}
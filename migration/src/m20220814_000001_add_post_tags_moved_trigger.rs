@@ -0,0 +1,52 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220814_000001_add_post_tags_moved_trigger"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        // Merging tags moves the post_tags rows to another tag instead of re-inserting them.
+        let sql = r#"
+        CREATE TRIGGER `posts_search_tag_moved` AFTER UPDATE OF tag_uuid ON `post_tags`
+        BEGIN
+            UPDATE `posts_search` SET tags = COALESCE((
+                SELECT group_concat(tags.name, ' ')
+                FROM post_tags
+                INNER JOIN tags ON tags.uuid = post_tags.tag_uuid
+                WHERE post_tags.post_uuid = new.post_uuid
+            ), '')
+            WHERE uuid = new.post_uuid;
+        END;
+        "#;
+        let tag_moved = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(tag_moved).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TRIGGER `posts_search_tag_moved`;
+        "#;
+        let tag_moved = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(tag_moved).await?;
+
+        Ok(())
+    }
+}
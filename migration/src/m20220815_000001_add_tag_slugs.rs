@@ -0,0 +1,112 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+use std::collections::HashSet;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220815_000001_add_tag_slugs"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        ALTER TABLE `tags` ADD COLUMN slug TEXT NOT NULL DEFAULT '';
+        "#;
+        let slug = Statement::from_string(backend, sql.to_owned());
+
+        // Markdown, rendered when it's read.
+        let sql = r#"
+        ALTER TABLE `tags` ADD COLUMN description TEXT NULL;
+        "#;
+        let description = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(slug).await?;
+        conn.execute(description).await?;
+
+        // Existing tags need a slug before the unique index can be created.
+        let sql = r#"
+        SELECT uuid, name FROM `tags` ORDER BY created_at ASC;
+        "#;
+        let rows = conn
+            .query_all(Statement::from_string(backend, sql.to_owned()))
+            .await?;
+
+        let mut taken: HashSet<String> = HashSet::new();
+        for row in rows {
+            let uuid: Vec<u8> = row.try_get("", "uuid")?;
+            let name: String = row.try_get("", "name")?;
+
+            let base = slugify(&name);
+            let mut slug = base.clone();
+            let mut suffix = 2;
+            while taken.contains(&slug) {
+                slug = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+            taken.insert(slug.clone());
+
+            let update = Statement::from_sql_and_values(
+                backend,
+                "UPDATE `tags` SET slug = ? WHERE uuid = ?",
+                vec![slug.into(), uuid.into()],
+            );
+            conn.execute(update).await?;
+        }
+
+        let sql = r#"
+        CREATE UNIQUE INDEX `tags_slug_idx` ON `tags` (slug);
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+        conn.execute(index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP INDEX `tags_slug_idx`;
+        "#;
+        let index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `tags` DROP COLUMN description;
+        "#;
+        let description = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `tags` DROP COLUMN slug;
+        "#;
+        let slug = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(index).await?;
+        conn.execute(description).await?;
+        conn.execute(slug).await?;
+
+        Ok(())
+    }
+}
+
+fn slugify(text: &str) -> String {
+    let slug = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+
+    match slug.as_str() {
+        "" => String::from("tag"),
+        _ => slug,
+    }
+}
@@ -0,0 +1,58 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220816_000001_add_tag_parents"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        // Tags without a parent are at the top of the hierarchy.
+        let sql = r#"
+        ALTER TABLE `tags` ADD COLUMN parent_uuid BLOB NULL
+            REFERENCES tags (uuid)
+                ON DELETE SET NULL
+                ON UPDATE CASCADE;
+        "#;
+        let parent_uuid = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `tags_parent_uuid_idx` ON `tags` (parent_uuid);
+        "#;
+        let parent_index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(parent_uuid).await?;
+        conn.execute(parent_index).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP INDEX `tags_parent_uuid_idx`;
+        "#;
+        let parent_index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `tags` DROP COLUMN parent_uuid;
+        "#;
+        let parent_uuid = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(parent_index).await?;
+        conn.execute(parent_uuid).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{tag_duplicates, tag_names::name_key};
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20220817_000001_add_tag_synonyms"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        // What tag names are matched on, so `Rust` and ` rust` end up being the same tag.
+        let sql = r#"
+        ALTER TABLE `tags` ADD COLUMN normalized_name TEXT NOT NULL DEFAULT '';
+        "#;
+        let normalized_name = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(normalized_name).await?;
+
+        let sql = r#"
+        SELECT uuid, name FROM `tags`;
+        "#;
+        let rows = conn
+            .query_all(Statement::from_string(backend, sql.to_owned()))
+            .await?;
+
+        for row in rows {
+            let uuid: Vec<u8> = row.try_get("", "uuid")?;
+            let name: String = row.try_get("", "name")?;
+
+            let update = Statement::from_sql_and_values(
+                backend,
+                "UPDATE `tags` SET normalized_name = ? WHERE uuid = ?",
+                vec![name_key(&name).into(), uuid.into()],
+            );
+            conn.execute(update).await?;
+        }
+
+        // Not unique, existing tags may only differ by their case until they get merged.
+        let sql = r#"
+        CREATE INDEX `tags_normalized_name_idx` ON `tags` (normalized_name);
+        "#;
+        let normalized_name_index = Statement::from_string(backend, sql.to_owned());
+
+        // Aliases are stored normalized, a tag given as one of them is the canonical tag.
+        let sql = r#"
+        CREATE TABLE `tag_synonyms` (
+            uuid BLOB PRIMARY KEY NOT NULL,
+            alias TEXT UNIQUE NOT NULL,
+            tag_uuid BLOB NOT NULL,
+            created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+            FOREIGN KEY (tag_uuid)
+            REFERENCES tags (uuid)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+        );
+        "#;
+        let tag_synonyms = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        CREATE INDEX `tag_synonyms_tag_uuid_idx` ON `tag_synonyms` (tag_uuid);
+        "#;
+        let tag_synonyms_index = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(normalized_name_index).await?;
+        conn.execute(tag_synonyms).await?;
+        conn.execute(tag_synonyms_index).await?;
+
+        let duplicates = tag_duplicates::find(conn).await?;
+        tag_duplicates::print(&duplicates);
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let conn = manager.get_connection();
+
+        let sql = r#"
+        DROP TABLE `tag_synonyms`;
+        "#;
+        let tag_synonyms = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        DROP INDEX `tags_normalized_name_idx`;
+        "#;
+        let normalized_name_index = Statement::from_string(backend, sql.to_owned());
+
+        let sql = r#"
+        ALTER TABLE `tags` DROP COLUMN normalized_name;
+        "#;
+        let normalized_name = Statement::from_string(backend, sql.to_owned());
+
+        conn.execute(tag_synonyms).await?;
+        conn.execute(normalized_name_index).await?;
+        conn.execute(normalized_name).await?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,25 @@
+use migration::tag_duplicates;
 use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::Database;
+use std::env;
 
 #[async_std::main]
 async fn main() {
+    // Not a migration, it reports the tags that look like duplicates so they can be merged.
+    if env::args().nth(1).as_deref() == Some("tag-duplicates") {
+        dotenv::dotenv().ok();
+
+        let url = env::var("DATABASE_URL").expect("Environment variable 'DATABASE_URL' not set");
+        let conn = Database::connect(&url)
+            .await
+            .expect("Failed to connect to the database");
+        let duplicates = tag_duplicates::find(&conn)
+            .await
+            .expect("Failed to look for duplicated tags");
+
+        tag_duplicates::print(&duplicates);
+        return;
+    }
+
     cli::run_cli(migration::Migrator).await;
 }
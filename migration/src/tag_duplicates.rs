@@ -0,0 +1,66 @@
+//! Tags that are most likely the same one typed differently, like `Rust`, `rust ` and
+//! `rust-lang` next to `Rust Lang`. They are only reported, merging them is left to the admins.
+
+use crate::tag_names::name_key;
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct DuplicateTag {
+    pub name: String,
+    pub slug: String,
+}
+
+/// Looser than the key the API matches on, punctuation and spaces don't count either.
+fn near_key(name: &str) -> String {
+    name_key(name)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// Groups of tags sharing the same near key, groups of a single tag are left out.
+pub async fn find<C: ConnectionTrait>(conn: &C) -> Result<Vec<Vec<DuplicateTag>>, DbErr> {
+    let backend = conn.get_database_backend();
+
+    let sql = r#"
+    SELECT name, slug FROM `tags` ORDER BY name ASC;
+    "#;
+    let rows = conn
+        .query_all(Statement::from_string(backend, sql.to_owned()))
+        .await?;
+
+    let mut groups: BTreeMap<String, Vec<DuplicateTag>> = BTreeMap::new();
+    for row in rows {
+        let name: String = row.try_get("", "name")?;
+        let slug: String = row.try_get("", "slug")?;
+
+        groups
+            .entry(near_key(&name))
+            .or_default()
+            .push(DuplicateTag { name, slug });
+    }
+
+    Ok(groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect())
+}
+
+pub fn print(groups: &[Vec<DuplicateTag>]) {
+    if groups.is_empty() {
+        println!("No duplicated tags found");
+        return;
+    }
+
+    println!("Found {} groups of tags that look the same:", groups.len());
+    for group in groups {
+        let tags: Vec<String> = group
+            .iter()
+            .map(|tag| format!("'{}' ({})", tag.name, tag.slug))
+            .collect();
+
+        println!("  - {}", tags.join(", "));
+    }
+    println!("Merge them with the mergeTags mutation, or add synonyms for the spellings to keep.");
+}
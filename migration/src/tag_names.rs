@@ -0,0 +1,18 @@
+//! How tag names are normalized, shared by the API and the migrations.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// How a tag name is stored: NFC, trimmed and with runs of whitespace turned into one space,
+/// e.g. `" Rust\t Lang "` becomes `"Rust Lang"`. The case is kept for display.
+pub fn normalize_name(name: &str) -> String {
+    name.nfc()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// What tag names and synonyms are matched on, two names with the same key are the same tag.
+pub fn name_key(name: &str) -> String {
+    normalize_name(name).to_lowercase().nfc().collect()
+}
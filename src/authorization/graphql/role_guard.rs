@@ -13,9 +13,14 @@ use strum_macros::Display;
 #[derive(Eq, PartialEq, Display)]
 pub enum Resource {
     Post,
-    // Tag,
+    Tag,
     User,
     File,
+    Webhook,
+    Build,
+    Series,
+    /// Rewrites every stored post at once, no policy grants it so only admins get in.
+    Sanitizer,
 }
 
 #[derive(Eq, PartialEq, Display)]
@@ -37,12 +42,13 @@ impl RoleGuard {
         }
     }
 
-    // pub fn default() -> Self {
-    //     RoleGuard {
-    //         resource: None,
-    //         action: None,
-    //     }
-    // }
+    /// Lets in anyone with a valid token, whatever their role.
+    pub fn authenticated() -> Self {
+        RoleGuard {
+            resource: None,
+            action: None,
+        }
+    }
 }
 
 impl RoleGuard {
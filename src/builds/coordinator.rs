@@ -0,0 +1,125 @@
+use crate::{
+    builds::{BuildRequest, BuildsRepository},
+    db::Db,
+    utils::datetime::get_now,
+};
+use chrono::Duration;
+use reqwest::Client;
+use rocket::{
+    fairing::AdHoc,
+    tokio::{self, time},
+};
+use sea_orm::DatabaseConnection;
+use sea_orm_rocket::Database;
+use std::{env, time::Duration as StdDuration};
+
+/// Only the start of the hook's answer is kept in the build log. The hook URL itself is left out
+/// of it since deploy hooks usually carry their token in the URL.
+const MAX_LOG_LENGTH: usize = 4000;
+
+/// How long a build waits for more changes before it runs, from `BUILD_DEBOUNCE_SECS`.
+pub fn debounce() -> Duration {
+    let secs = env::var("BUILD_DEBOUNCE_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<i64>().ok())
+        .unwrap_or(60);
+
+    Duration::seconds(secs)
+}
+
+/// Queues a rebuild after the content changed. The change itself already went through, so a
+/// failure here is only logged.
+pub async fn notify_change(conn: &DatabaseConnection, reason: impl Into<String>) {
+    let request = BuildRequest {
+        reason: reason.into(),
+        triggered_by: None,
+        debounce: debounce(),
+    };
+
+    if let Err(err) = BuildsRepository::request(conn, request).await {
+        error!("Failed to queue a build: {}", err);
+    }
+}
+
+/// Fairing that starts the background task running the queued builds one at a time, by calling
+/// the deploy hook of the static site.
+pub fn init_coordinator() -> AdHoc {
+    AdHoc::on_liftoff("Build Coordinator", |rocket| {
+        Box::pin(async move {
+            let conn = match Db::fetch(rocket) {
+                Some(db) => db.conn.clone(),
+                None => {
+                    error!("Database is not available, builds won't run");
+                    return;
+                }
+            };
+
+            match BuildsRepository::fail_interrupted(&conn).await {
+                Ok(0) => {}
+                Ok(interrupted) => warn!("Marked {} interrupted builds as failed", interrupted),
+                Err(err) => error!("Failed to clean up interrupted builds: {}", err),
+            }
+
+            let interval = env::var("BUILDS_INTERVAL_SECS")
+                .ok()
+                .and_then(|secs| secs.parse::<u64>().ok())
+                .unwrap_or(5);
+            let hook = env::var("BUILD_HOOK_URL")
+                .ok()
+                .filter(|url| !url.is_empty());
+            let client = Client::new();
+
+            tokio::spawn(async move {
+                let mut interval = time::interval(StdDuration::from_secs(interval));
+
+                loop {
+                    interval.tick().await;
+
+                    let build = match BuildsRepository::next_due(&conn, get_now()).await {
+                        Ok(Some(build)) => build,
+                        Ok(None) => continue,
+                        Err(err) => {
+                            error!("Failed to load the queued builds: {}", err);
+                            continue;
+                        }
+                    };
+
+                    let build = match BuildsRepository::start(&conn, build).await {
+                        Ok(build) => build,
+                        Err(err) => {
+                            error!("Failed to start the build: {}", err);
+                            continue;
+                        }
+                    };
+
+                    let (succeeded, log) = run_hook(&client, hook.as_deref()).await;
+                    match BuildsRepository::finish(&conn, build, succeeded, log).await {
+                        Ok(build) => info!("Build finished: {}", build.status),
+                        Err(err) => error!("Failed to save the build result: {}", err),
+                    }
+                }
+            });
+        })
+    })
+}
+
+async fn run_hook(client: &Client, hook: Option<&str>) -> (bool, String) {
+    let hook = match hook {
+        Some(hook) => hook,
+        None => return (false, String::from("BUILD_HOOK_URL is not defined")),
+    };
+
+    let response = match client.post(hook).send().await {
+        Ok(response) => response,
+        Err(err) => return (false, format!("Build hook failed: {}", err.without_url())),
+    };
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let body: String = body.chars().take(MAX_LOG_LENGTH).collect();
+
+    (
+        status.is_success(),
+        format!("Build hook answered {}\n{}", status, body),
+    )
+}
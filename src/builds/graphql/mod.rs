@@ -0,0 +1,82 @@
+mod mutation;
+mod query;
+
+pub use mutation::*;
+pub use query::*;
+
+use async_graphql::{Enum, SimpleObject, ID};
+use chrono::{DateTime, Utc};
+use entity::{builds, enums::BuildStatus as DBBuildStatus};
+use snafu::prelude::*;
+use std::{convert::From, str::FromStr};
+use strum::ParseError;
+use uuid::Uuid;
+
+/// A rebuild of the static site. Content changes made close together share the same build.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct Build {
+    pub uuid: ID,
+    pub status: BuildStatus,
+    /// The latest change collapsed into this build.
+    pub reason: String,
+    /// How many changes were collapsed into this build.
+    pub changes: i32,
+    pub log: Option<String>,
+    pub triggered_by: Option<ID>,
+    pub run_after: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<builds::Model> for Build {
+    type Error = Error;
+
+    fn try_from(build: builds::Model) -> Result<Self, Self::Error> {
+        let uuid = Uuid::from_bytes(build.uuid());
+        let status = DBBuildStatus::from_str(&build.status).context(InvalidStatusSnafu { uuid })?;
+        let triggered_by = build
+            .triggered_by()
+            .map(|uuid| Uuid::from_bytes(uuid).into());
+
+        Ok(Build {
+            uuid: uuid.into(),
+            status: status.into(),
+            reason: build.reason,
+            changes: build.changes,
+            log: build.log,
+            triggered_by,
+            run_after: build.run_after,
+            started_at: build.started_at,
+            finished_at: build.finished_at,
+            created_at: build.created_at,
+            updated_at: build.updated_at,
+        })
+    }
+}
+
+#[derive(Enum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BuildStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl From<DBBuildStatus> for BuildStatus {
+    fn from(status: DBBuildStatus) -> Self {
+        match status {
+            DBBuildStatus::Queued => BuildStatus::Queued,
+            DBBuildStatus::Running => BuildStatus::Running,
+            DBBuildStatus::Succeeded => BuildStatus::Succeeded,
+            DBBuildStatus::Failed => BuildStatus::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Status: {} is not valid for Build: {}", source, uuid))]
+    InvalidStatus { source: ParseError, uuid: Uuid },
+}
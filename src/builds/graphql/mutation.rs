@@ -0,0 +1,31 @@
+use super::Build;
+use crate::{
+    authorization::graphql::{Action, Resource, RoleGuard},
+    builds::{BuildRequest, BuildsRepository},
+    graphql::context::{get_conn_from_context, get_token_from_context},
+};
+use async_graphql::{Context, Object, Result};
+use chrono::Duration;
+
+#[derive(Default)]
+pub struct BuildsMutation;
+
+#[Object]
+impl BuildsMutation {
+    /// Rebuilds the site right away, pending content changes are included in the same build.
+    #[graphql(guard = "RoleGuard::new(Resource::Build, Action::Write)")]
+    pub async fn trigger_build(&self, ctx: &Context<'_>) -> Result<Build> {
+        let conn = get_conn_from_context(ctx).await?;
+        let token = get_token_from_context(ctx).await?.expect("Missing Token");
+
+        let request = BuildRequest {
+            reason: String::from("Triggered manually"),
+            triggered_by: Some(token.uuid),
+            debounce: Duration::zero(),
+        };
+
+        let build = BuildsRepository::request(conn, request).await?;
+
+        Ok(build.try_into()?)
+    }
+}
@@ -0,0 +1,31 @@
+use super::Build;
+use crate::{
+    authorization::graphql::{Action, Resource, RoleGuard},
+    builds::BuildsRepository,
+    graphql::context::get_conn_from_context,
+};
+use async_graphql::{Context, Object, Result};
+
+/// Builds listed when no limit is given.
+const DEFAULT_BUILDS: u64 = 20;
+
+#[derive(Default)]
+pub struct BuildsQuery;
+
+#[Object]
+impl BuildsQuery {
+    /// Latest builds of the static site, newest first.
+    #[graphql(guard = "RoleGuard::new(Resource::Build, Action::Read)")]
+    pub async fn builds(&self, ctx: &Context<'_>, limit: Option<u64>) -> Result<Vec<Build>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let limit = limit.unwrap_or(DEFAULT_BUILDS);
+
+        let builds = BuildsRepository::find_latest(conn, limit)
+            .await?
+            .into_iter()
+            .map(Build::try_from)
+            .collect::<Result<Vec<Build>, _>>()?;
+
+        Ok(builds)
+    }
+}
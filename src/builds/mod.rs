@@ -0,0 +1,5 @@
+pub mod coordinator;
+pub mod graphql;
+mod repository;
+
+pub use repository::*;
@@ -0,0 +1,255 @@
+use crate::utils::datetime::get_now;
+use chrono::{DateTime, Duration, Utc};
+use entity::{
+    builds::{self, Entity as Build},
+    enums::BuildStatus,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, Statement, TransactionTrait,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+/// A queued build never waits longer than this many debounce windows, so a steady stream of
+/// edits can't hold it back forever.
+const MAX_DEBOUNCES: i32 = 10;
+
+pub struct BuildRequest {
+    pub reason: String,
+    pub triggered_by: Option<Uuid>,
+    /// How long to wait for more changes before running, zero runs it on the next tick.
+    pub debounce: Duration,
+}
+
+pub struct BuildsRepository;
+
+impl BuildsRepository {
+    /// Collapses the request into the queued build when there is one, pushing it back by the
+    /// debounce window. Otherwise it queues a new build.
+    pub async fn request(conn: &DatabaseConnection, input: BuildRequest) -> Result<builds::Model> {
+        let now = get_now();
+        let uuid = Uuid::new_v4();
+        let triggered_by = input.triggered_by.map(|uuid| uuid.as_bytes().to_vec());
+
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        // Only one build can be queued, `builds_queued_idx` turns the insert into a no-op when
+        // there's one already. Writing first also takes the lock, so concurrent requests queue up
+        // behind this one instead of each seeing no queued build.
+        let sql = r#"
+        INSERT OR IGNORE INTO `builds`
+            (uuid, status, reason, changes, triggered_by, run_after, created_at, updated_at)
+        VALUES (?, ?, ?, 1, ?, ?, ?, ?);
+        "#;
+        let insert = Statement::from_sql_and_values(
+            txn.get_database_backend(),
+            sql,
+            vec![
+                uuid.as_bytes().to_vec().into(),
+                BuildStatus::Queued.to_string().into(),
+                input.reason.clone().into(),
+                triggered_by.clone().into(),
+                (now + input.debounce).into(),
+                now.into(),
+                now.into(),
+            ],
+        );
+        let inserted = txn.execute(insert).await.context(QueryFailedSnafu)?;
+
+        if inserted.rows_affected() > 0 {
+            let build = Build::find_by_id(uuid.as_bytes().to_vec())
+                .one(&txn)
+                .await
+                .context(QueryFailedSnafu)?
+                .context(BuildNotFoundSnafu { uuid })?;
+            txn.commit().await.context(QueryFailedSnafu)?;
+
+            return Ok(build);
+        }
+
+        let queued = Build::find()
+            .filter(builds::Column::Status.eq(BuildStatus::Queued.to_string()))
+            .one(&txn)
+            .await
+            .context(QueryFailedSnafu)?
+            .context(QueuedBuildNotFoundSnafu)?;
+
+        let deadline = queued.created_at + input.debounce * MAX_DEBOUNCES;
+        let run_after = (now + input.debounce).min(deadline);
+        let changes = queued.changes + 1;
+
+        let mut build: builds::ActiveModel = queued.into();
+        build.reason = Set(input.reason);
+        build.changes = Set(changes);
+        build.run_after = Set(run_after);
+        build.updated_at = Set(now);
+        if triggered_by.is_some() {
+            build.triggered_by = Set(triggered_by);
+        }
+
+        let build = build.update(&txn).await.context(QueryFailedSnafu)?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(build)
+    }
+
+    /// The next build to run, as long as no other build is running.
+    pub async fn next_due(
+        conn: &DatabaseConnection,
+        now: DateTime<Utc>,
+    ) -> Result<Option<builds::Model>> {
+        let running = Build::find()
+            .filter(builds::Column::Status.eq(BuildStatus::Running.to_string()))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        if running.is_some() {
+            return Ok(None);
+        }
+
+        Build::find()
+            .filter(builds::Column::Status.eq(BuildStatus::Queued.to_string()))
+            .filter(builds::Column::RunAfter.lte(now))
+            .order_by_asc(builds::Column::RunAfter)
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    pub async fn start(conn: &DatabaseConnection, build: builds::Model) -> Result<builds::Model> {
+        let now = get_now();
+
+        let mut build: builds::ActiveModel = build.into();
+        build.status = Set(BuildStatus::Running.to_string());
+        build.started_at = Set(Some(now));
+        build.updated_at = Set(now);
+
+        build.update(conn).await.context(QueryFailedSnafu)
+    }
+
+    pub async fn finish(
+        conn: &DatabaseConnection,
+        build: builds::Model,
+        succeeded: bool,
+        log: String,
+    ) -> Result<builds::Model> {
+        let now = get_now();
+        let status = match succeeded {
+            true => BuildStatus::Succeeded,
+            false => BuildStatus::Failed,
+        };
+
+        let mut build: builds::ActiveModel = build.into();
+        build.status = Set(status.to_string());
+        build.log = Set(Some(log));
+        build.finished_at = Set(Some(now));
+        build.updated_at = Set(now);
+
+        build.update(conn).await.context(QueryFailedSnafu)
+    }
+
+    /// Builds left running when the server stopped will never finish, they are marked as failed.
+    pub async fn fail_interrupted(conn: &DatabaseConnection) -> Result<usize> {
+        let running = Build::find()
+            .filter(builds::Column::Status.eq(BuildStatus::Running.to_string()))
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let interrupted = running.len();
+        for build in running {
+            let log = String::from("Interrupted, the server stopped while the build was running");
+            BuildsRepository::finish(conn, build, false, log).await?;
+        }
+
+        Ok(interrupted)
+    }
+
+    pub async fn find_by_id(
+        conn: &DatabaseConnection,
+        uuid: Uuid,
+    ) -> Result<Option<builds::Model>> {
+        Build::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Latest builds, newest first.
+    pub async fn find_latest(conn: &DatabaseConnection, limit: u64) -> Result<Vec<builds::Model>> {
+        Build::find()
+            .order_by_desc(builds::Column::CreatedAt)
+            .limit(limit)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Builds Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Build does not exist with uuid {}", uuid))]
+    BuildNotFound { uuid: Uuid },
+
+    #[snafu(display("No build is queued to collapse the request into"))]
+    QueuedBuildNotFound,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing;
+
+    fn change(reason: &str) -> BuildRequest {
+        BuildRequest {
+            reason: reason.to_string(),
+            triggered_by: None,
+            debounce: Duration::seconds(60),
+        }
+    }
+
+    #[rocket::async_test]
+    async fn collapses_concurrent_requests_into_one_build() {
+        let conn = testing::connect().await;
+
+        let (a, b, c, d, e) = rocket::tokio::join!(
+            BuildsRepository::request(&conn, change("Post created")),
+            BuildsRepository::request(&conn, change("Post updated")),
+            BuildsRepository::request(&conn, change("Post updated")),
+            BuildsRepository::request(&conn, change("Post updated")),
+            BuildsRepository::request(&conn, change("Post published")),
+        );
+        let requested = [a, b, c, d, e].map(|build| build.unwrap());
+
+        assert!(requested
+            .iter()
+            .all(|build| build.uuid == requested[0].uuid));
+
+        let builds = BuildsRepository::find_latest(&conn, 10).await.unwrap();
+        assert_eq!(builds.len(), 1);
+        assert_eq!(builds[0].changes, 5);
+        assert_eq!(builds[0].status, BuildStatus::Queued.to_string());
+    }
+
+    #[rocket::async_test]
+    async fn rejects_a_second_queued_build() {
+        let conn = testing::connect().await;
+        BuildsRepository::request(&conn, change("Post created"))
+            .await
+            .unwrap();
+
+        // What a request that missed the queued build would try to do.
+        let duplicate = builds::ActiveModel {
+            reason: Set(String::from("Post updated")),
+            ..Default::default()
+        };
+        assert!(Build::insert(duplicate).exec(&conn).await.is_err());
+    }
+}
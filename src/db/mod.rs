@@ -1,2 +1,5 @@
 mod pool;
 pub use pool::*;
+
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,108 @@
+//! In-memory databases for the tests, migrated the same way the server does on launch.
+
+use crate::{
+    posts::{ChangePostStatusInput, NewPostInput, PostsRepository},
+    utils::uuid::get_uuid_bytes,
+};
+use entity::{
+    enums::{Role, Status},
+    users::{self, Entity as User},
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseConnection, EntityTrait, Set, Statement,
+};
+use uuid::Uuid;
+
+pub async fn connect() -> DatabaseConnection {
+    // Every connection to `sqlite::memory:` opens its own empty database, so there's only one.
+    let mut options = ConnectOptions::new(String::from("sqlite::memory:"));
+    options.max_connections(1).min_connections(1);
+
+    let conn = Database::connect(options)
+        .await
+        .expect("Failed to open the test database");
+    Migrator::up(&conn, None)
+        .await
+        .expect("Failed to migrate the test database");
+
+    conn
+}
+
+pub async fn create_user(conn: &DatabaseConnection, email: &str) -> Uuid {
+    let user = users::ActiveModel {
+        email: Set(email.to_string()),
+        password: Set(String::from("not-a-hash")),
+        role: Set(Role::Admin.to_string()),
+        ..Default::default()
+    };
+
+    let result = User::insert(user)
+        .exec(conn)
+        .await
+        .expect("Failed to create the test user");
+
+    Uuid::from_bytes(get_uuid_bytes(&result.last_insert_id))
+}
+
+/// An English post whose title is also its content.
+pub fn new_post(user: Uuid, title: &str, tags: &[&str]) -> NewPostInput {
+    NewPostInput {
+        title: title.to_string(),
+        slug: None,
+        raw: title.to_string(),
+        html: title.to_string(),
+        excerpt: None,
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        locale: String::from("en"),
+        translation_of: None,
+        created_by: user,
+    }
+}
+
+/// Creates the post and publishes it right away.
+pub async fn publish(conn: &DatabaseConnection, input: NewPostInput) -> Uuid {
+    let post = PostsRepository::create(conn, input)
+        .await
+        .expect("Failed to create the test post");
+    let uuid = Uuid::from_bytes(post.uuid());
+
+    let input = ChangePostStatusInput {
+        uuid,
+        status: Status::Published,
+        publish_at: None,
+    };
+    PostsRepository::change_post_status(conn, input)
+        .await
+        .expect("Failed to publish the test post");
+
+    uuid
+}
+
+/// Makes every insert into `table` fail from now on, like a step failing halfway through.
+pub async fn fail_inserts_into(conn: &DatabaseConnection, table: &str) {
+    let sql = format!(
+        r#"
+        CREATE TRIGGER fail_inserts_into_{0} BEFORE INSERT ON `{0}`
+        BEGIN
+            SELECT RAISE(ABORT, 'forced failure');
+        END;
+        "#,
+        table
+    );
+
+    conn.execute(Statement::from_string(conn.get_database_backend(), sql))
+        .await
+        .expect("Failed to create the failing trigger");
+}
+
+pub async fn count_rows(conn: &DatabaseConnection, table: &str) -> i64 {
+    let sql = format!("SELECT COUNT(*) AS count FROM `{}`", table);
+
+    conn.query_one(Statement::from_string(conn.get_database_backend(), sql))
+        .await
+        .expect("Failed to count the rows")
+        .expect("COUNT(*) always returns a row")
+        .try_get("", "count")
+        .expect("Failed to read the count")
+}
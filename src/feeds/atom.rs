@@ -0,0 +1,91 @@
+use super::Feed;
+use crate::utils::xml::escape;
+use std::fmt::Write;
+
+/// Atom 1.0, entries carry the excerpt as summary and the full HTML as content.
+pub fn render(feed: &Feed) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"{}\">",
+        escape(&feed.language)
+    );
+
+    let _ = writeln!(xml, "<id>{}</id>", escape(&feed.feed_url));
+    let _ = writeln!(xml, "<title>{}</title>", escape(&feed.title));
+    if !feed.description.is_empty() {
+        let _ = writeln!(xml, "<subtitle>{}</subtitle>", escape(&feed.description));
+    }
+    let _ = writeln!(
+        xml,
+        "<link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>",
+        escape(&feed.feed_url)
+    );
+    let _ = writeln!(
+        xml,
+        "<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>",
+        escape(&feed.home_url)
+    );
+    let _ = writeln!(xml, "<updated>{}</updated>", feed.updated_at.to_rfc3339());
+    if !feed.author.is_empty() {
+        let _ = writeln!(
+            xml,
+            "<author><name>{}</name></author>",
+            escape(&feed.author)
+        );
+    }
+
+    for entry in &feed.entries {
+        let _ = writeln!(xml, "<entry xml:lang=\"{}\">", escape(&entry.locale));
+        let _ = writeln!(xml, "<id>{}</id>", entry.id);
+        let _ = writeln!(xml, "<title>{}</title>", escape(&entry.title));
+        let _ = writeln!(
+            xml,
+            "<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>",
+            escape(&entry.url)
+        );
+        for alternate in &entry.alternates {
+            let _ = writeln!(
+                xml,
+                "<link rel=\"alternate\" type=\"text/html\" hreflang=\"{}\" href=\"{}\"/>",
+                escape(&alternate.locale),
+                escape(&alternate.url)
+            );
+        }
+        let _ = writeln!(
+            xml,
+            "<published>{}</published>",
+            entry.published_at.to_rfc3339()
+        );
+        let _ = writeln!(xml, "<updated>{}</updated>", entry.updated_at.to_rfc3339());
+        for author in &entry.authors {
+            let _ = match &author.url {
+                Some(url) => writeln!(
+                    xml,
+                    "<author><name>{}</name><uri>{}</uri></author>",
+                    escape(&author.name),
+                    escape(url)
+                ),
+                None => writeln!(
+                    xml,
+                    "<author><name>{}</name></author>",
+                    escape(&author.name)
+                ),
+            };
+        }
+        for tag in &entry.tags {
+            let _ = writeln!(xml, "<category term=\"{}\"/>", escape(tag));
+        }
+        let _ = writeln!(xml, "<summary>{}</summary>", escape(&entry.summary));
+        let _ = writeln!(
+            xml,
+            "<content type=\"html\">{}</content>",
+            escape(&entry.html)
+        );
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+
+    xml
+}
@@ -0,0 +1,289 @@
+use crate::{
+    markdown::derive_excerpt,
+    posts::{
+        graphql::PostAuthorUuid, AuthorsError, Error as PostsError, PostAuthorsRepository,
+        PostTranslationsRepository, PostsRepository, TranslationsError,
+    },
+    tags::{graphql::PostTagUuid, Error as TagsError, TagsRepository},
+    user::{graphql::UserUuid, Error as UsersError, UserRepository},
+    utils::{
+        locale::Alternate,
+        site::{encode_path_segment, SiteConfig},
+    },
+};
+use chrono::{DateTime, Utc};
+use entity::{enums::CreditRole, posts, tags, users};
+use sea_orm::DatabaseConnection;
+use snafu::prelude::*;
+use std::{collections::HashMap, time::UNIX_EPOCH};
+use uuid::Uuid;
+
+/// How many of the latest posts go into a feed.
+pub const FEED_SIZE: u64 = 20;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "feed.xml",
+            FeedFormat::Atom => "atom.xml",
+            FeedFormat::Json => "feed.json",
+        }
+    }
+
+    /// Where the feed is served from, tag feeds live under the slug of the tag.
+    pub fn path(&self, tag: Option<&str>) -> String {
+        match tag {
+            Some(slug) => format!("/tags/{}/{}", encode_path_segment(slug), self.file_name()),
+            None => format!("/{}", self.file_name()),
+        }
+    }
+
+    pub fn render(&self, feed: &Feed) -> String {
+        match self {
+            FeedFormat::Rss => super::rss::render(feed),
+            FeedFormat::Atom => super::atom::render(feed),
+            FeedFormat::Json => super::json::render(feed),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub title: String,
+    pub description: String,
+    pub home_url: String,
+    pub feed_url: String,
+    pub author: String,
+    pub language: String,
+    pub updated_at: DateTime<Utc>,
+    pub entries: Vec<FeedEntry>,
+}
+
+/// Someone credited as an author of a post, by the name shown on the site. Never their email.
+#[derive(Debug, Clone)]
+pub struct FeedAuthor {
+    pub name: String,
+    /// Their author page, only the authors with a handle have one.
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub authors: Vec<FeedAuthor>,
+    pub tags: Vec<String>,
+    pub summary: String,
+    pub html: String,
+    pub locale: String,
+    /// Published translations of the post.
+    pub alternates: Vec<Alternate>,
+    pub published_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Feed {
+    pub async fn load(
+        conn: &DatabaseConnection,
+        site: &SiteConfig,
+        format: FeedFormat,
+        tag: Option<tags::Model>,
+    ) -> Result<Feed> {
+        let tagged = tag.as_ref().map(|tag| Uuid::from_bytes(tag.uuid()));
+        let posts = PostsRepository::find_latest_published(conn, tagged, FEED_SIZE)
+            .await
+            .context(FailedToLoadPostsSnafu)?;
+
+        let ids: Vec<PostTagUuid> = posts
+            .iter()
+            .map(|post| PostTagUuid::new(Uuid::from_bytes(post.uuid())))
+            .collect();
+        let mut tags: HashMap<Uuid, Vec<String>> = TagsRepository::find_by_post_ids(conn, &ids)
+            .await
+            .context(FailedToLoadTagsSnafu)?
+            .into_iter()
+            .map(|(uuid, tags)| (uuid, tags.into_iter().map(|t| t.name).collect()))
+            .collect();
+
+        let mut authors = FeedAuthor::load(conn, site, &posts).await?;
+
+        let groups: Vec<Vec<u8>> = posts
+            .iter()
+            .filter_map(|post| post.translation_group.clone())
+            .collect();
+        let translations = PostTranslationsRepository::find_published_in_groups(conn, groups)
+            .await
+            .context(FailedToLoadTranslationsSnafu)?;
+
+        // An empty feed still needs a stable date, otherwise it would never be cached.
+        let updated_at = posts
+            .iter()
+            .map(|post| post.updated_at)
+            .max()
+            .unwrap_or_else(|| DateTime::<Utc>::from(UNIX_EPOCH));
+
+        let entries = posts
+            .into_iter()
+            .map(|post| {
+                let uuid = Uuid::from_bytes(post.uuid());
+                let tags = tags.remove(&uuid).unwrap_or_default();
+                let authors = authors.remove(&uuid).unwrap_or_default();
+                let alternates = translations
+                    .iter()
+                    .filter(|t| {
+                        t.uuid != post.uuid && t.translation_group == post.translation_group
+                    })
+                    .map(|t| Alternate {
+                        locale: t.locale.clone(),
+                        url: site.post_url(&t.slug),
+                    })
+                    .collect();
+
+                FeedEntry::new(site, post, authors, tags, alternates)
+            })
+            .collect();
+
+        let title = match &tag {
+            Some(tag) => format!("{} - {}", site.title, tag.name),
+            None => site.title.clone(),
+        };
+
+        Ok(Feed {
+            title,
+            description: site.description.clone(),
+            home_url: site.url.clone(),
+            feed_url: site.api_url(&format.path(tag.map(|tag| tag.slug).as_deref())),
+            author: site.author.clone(),
+            language: site.locale.clone(),
+            updated_at,
+            entries,
+        })
+    }
+}
+
+impl FeedEntry {
+    fn new(
+        site: &SiteConfig,
+        post: posts::Model,
+        authors: Vec<FeedAuthor>,
+        tags: Vec<String>,
+        alternates: Vec<Alternate>,
+    ) -> Self {
+        let uuid = Uuid::from_bytes(post.uuid());
+        let summary = match post.excerpt {
+            Some(excerpt) => excerpt,
+            None => derive_excerpt(&post.raw),
+        };
+
+        FeedEntry {
+            id: format!("urn:uuid:{}", uuid),
+            url: site.post_url(&post.slug),
+            title: post.title,
+            authors,
+            tags,
+            summary,
+            html: post.html,
+            locale: post.locale,
+            alternates,
+            published_at: post.published_at.unwrap_or(post.created_at),
+            updated_at: post.updated_at,
+        }
+    }
+}
+
+impl FeedAuthor {
+    /// The authors and co-authors of each post, in credit order. Credited users without a
+    /// display name or a handle have no name to show and are left out.
+    async fn load(
+        conn: &DatabaseConnection,
+        site: &SiteConfig,
+        posts: &[posts::Model],
+    ) -> Result<HashMap<Uuid, Vec<FeedAuthor>>> {
+        let ids: Vec<PostAuthorUuid> = posts
+            .iter()
+            .map(|post| PostAuthorUuid::new(Uuid::from_bytes(post.uuid())))
+            .collect();
+        let credits = PostAuthorsRepository::find_by_post_ids(conn, &ids)
+            .await
+            .context(FailedToLoadCreditsSnafu)?;
+
+        let authors = [CreditRole::Author, CreditRole::CoAuthor].map(|role| role.to_string());
+        let credits: Vec<(Uuid, Vec<Uuid>)> = credits
+            .into_iter()
+            .map(|(post, credits)| {
+                let users = credits
+                    .into_iter()
+                    .filter(|credit| authors.contains(&credit.role))
+                    .map(|credit| Uuid::from_bytes(credit.user_uuid()))
+                    .collect();
+
+                (post, users)
+            })
+            .collect();
+
+        let mut user_ids: Vec<UserUuid> = vec![];
+        for uuid in credits.iter().flat_map(|(_, users)| users) {
+            if !user_ids.iter().any(|id| &id.0 == uuid) {
+                user_ids.push(UserUuid::new(*uuid));
+            }
+        }
+        let users: HashMap<Uuid, users::Model> = UserRepository::find_by_ids(conn, &user_ids)
+            .await
+            .context(FailedToLoadUsersSnafu)?
+            .into_iter()
+            .map(|user| (Uuid::from_bytes(user.uuid()), user))
+            .collect();
+
+        let authors = credits
+            .into_iter()
+            .map(|(post, credited)| {
+                let authors = credited
+                    .iter()
+                    .filter_map(|uuid| users.get(uuid))
+                    .filter_map(|user| FeedAuthor::new(site, user))
+                    .collect();
+
+                (post, authors)
+            })
+            .collect();
+
+        Ok(authors)
+    }
+
+    fn new(site: &SiteConfig, user: &users::Model) -> Option<Self> {
+        let name = user.display_name.clone().or_else(|| user.handle.clone())?;
+
+        Some(FeedAuthor {
+            name,
+            url: user.handle.as_deref().map(|handle| site.author_url(handle)),
+        })
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Failed to load the posts of the feed: {}", source))]
+    FailedToLoadPosts { source: PostsError },
+
+    #[snafu(display("Failed to load the tags of the feed: {}", source))]
+    FailedToLoadTags { source: TagsError },
+
+    #[snafu(display("Failed to load the credits of the feed: {}", source))]
+    FailedToLoadCredits { source: AuthorsError },
+
+    #[snafu(display("Failed to load the authors of the feed: {}", source))]
+    FailedToLoadUsers { source: UsersError },
+
+    #[snafu(display("Failed to load the translations of the feed: {}", source))]
+    FailedToLoadTranslations { source: TranslationsError },
+}
@@ -0,0 +1,217 @@
+use super::{Error as FeedError, Feed, FeedFormat};
+use crate::{
+    db::Db,
+    tags::{Error as TagsError, TagsRepository},
+    utils::site::SiteConfig,
+};
+use chrono::{DateTime, Utc};
+use data_encoding::HEXLOWER;
+use rocket::{
+    http::{ContentType, Status},
+    request::Request,
+    response::{self, Responder, Response},
+    serde::{
+        ser::{SerializeStruct, Serializer},
+        Serialize,
+    },
+    State,
+};
+use sea_orm_rocket::Connection;
+use sha2::{Digest, Sha256};
+use snafu::prelude::*;
+use std::io::Cursor;
+
+#[get("/feed.xml")]
+pub async fn rss_feed(conn: Connection<'_, Db>, site: &State<SiteConfig>) -> Result<FeedResponse> {
+    feed_response(conn, site, FeedFormat::Rss, None).await
+}
+
+#[get("/atom.xml")]
+pub async fn atom_feed(conn: Connection<'_, Db>, site: &State<SiteConfig>) -> Result<FeedResponse> {
+    feed_response(conn, site, FeedFormat::Atom, None).await
+}
+
+#[get("/feed.json")]
+pub async fn json_feed(conn: Connection<'_, Db>, site: &State<SiteConfig>) -> Result<FeedResponse> {
+    feed_response(conn, site, FeedFormat::Json, None).await
+}
+
+#[get("/tags/<slug>/feed.xml")]
+pub async fn tag_rss_feed(
+    slug: String,
+    conn: Connection<'_, Db>,
+    site: &State<SiteConfig>,
+) -> Result<FeedResponse> {
+    feed_response(conn, site, FeedFormat::Rss, Some(slug)).await
+}
+
+#[get("/tags/<slug>/atom.xml")]
+pub async fn tag_atom_feed(
+    slug: String,
+    conn: Connection<'_, Db>,
+    site: &State<SiteConfig>,
+) -> Result<FeedResponse> {
+    feed_response(conn, site, FeedFormat::Atom, Some(slug)).await
+}
+
+#[get("/tags/<slug>/feed.json")]
+pub async fn tag_json_feed(
+    slug: String,
+    conn: Connection<'_, Db>,
+    site: &State<SiteConfig>,
+) -> Result<FeedResponse> {
+    feed_response(conn, site, FeedFormat::Json, Some(slug)).await
+}
+
+async fn feed_response(
+    conn: Connection<'_, Db>,
+    site: &SiteConfig,
+    format: FeedFormat,
+    slug: Option<String>,
+) -> Result<FeedResponse> {
+    let conn = conn.into_inner();
+
+    let tag = match slug {
+        Some(slug) => {
+            let tag = TagsRepository::find_by_slug(conn, &slug)
+                .await
+                .context(FailedToFindTagSnafu)?
+                .context(MissingTagSnafu)?;
+
+            Some(tag)
+        }
+        None => None,
+    };
+
+    let feed = Feed::load(conn, site, format, tag)
+        .await
+        .context(FailedToLoadFeedSnafu)?;
+
+    Ok(FeedResponse::new(format, &feed))
+}
+
+/// A rendered feed. It answers with `304 Not Modified` when the client already has it by its
+/// `ETag`. `If-Modified-Since` isn't trusted: the newest date goes back when a post is
+/// unpublished, and renaming a tag changes the feed without changing any date.
+pub struct FeedResponse {
+    format: FeedFormat,
+    body: String,
+    etag: String,
+    last_modified: DateTime<Utc>,
+}
+
+impl FeedResponse {
+    pub fn new(format: FeedFormat, feed: &Feed) -> Self {
+        let body = format.render(feed);
+        let etag = format!("\"{}\"", HEXLOWER.encode(&Sha256::digest(body.as_bytes())));
+
+        FeedResponse {
+            format,
+            body,
+            etag,
+            last_modified: feed.updated_at,
+        }
+    }
+
+    fn content_type(&self) -> ContentType {
+        match self.format {
+            FeedFormat::Rss => ContentType::new("application", "rss+xml"),
+            FeedFormat::Atom => ContentType::new("application", "atom+xml"),
+            FeedFormat::Json => ContentType::new("application", "feed+json"),
+        }
+    }
+
+    fn is_fresh(&self, req: &Request<'_>) -> bool {
+        match req.headers().get_one("If-None-Match") {
+            Some(etags) => etags
+                .split(',')
+                .map(|etag| etag.trim())
+                .any(|etag| etag == "*" || etag.trim_start_matches("W/") == self.etag),
+            None => false,
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> Responder<'r, 'static> for FeedResponse {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let last_modified = self
+            .last_modified
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+
+        let mut response = Response::build();
+        response
+            .raw_header("ETag", self.etag.clone())
+            .raw_header("Last-Modified", last_modified)
+            .raw_header("Cache-Control", "no-cache");
+
+        if self.is_fresh(req) {
+            return response.status(Status::NotModified).ok();
+        }
+
+        response
+            .status(Status::Ok)
+            .header(self.content_type())
+            .sized_body(self.body.len(), Cursor::new(self.body))
+            .ok()
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Failed to find the tag: {}", source))]
+    FailedToFindTag { source: TagsError },
+
+    #[snafu(display("Tag does not exist"))]
+    MissingTag,
+
+    #[snafu(display("{}", source))]
+    FailedToLoadFeed { source: FeedError },
+}
+
+#[rocket::async_trait]
+impl<'r> Responder<'r, 'static> for Error {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let status: Status = match &self {
+            Error::MissingTag => Status::NotFound,
+            _ => Status::InternalServerError,
+        };
+
+        let serialized = serde_json::to_string(&self).unwrap();
+
+        Response::build()
+            .status(status)
+            .sized_body(serialized.len(), Cursor::new(serialized))
+            .header(ContentType::JSON)
+            .ok()
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let kind: String = match self {
+            Error::FailedToFindTag { source: err } => {
+                debug!("Failed to find tag {:?}", err);
+
+                String::from("FailedToFindTag")
+            }
+            Error::MissingTag => String::from("MissingTag"),
+            Error::FailedToLoadFeed { source: err } => {
+                debug!("Failed to load feed {:?}", err);
+
+                String::from("FailedToLoadFeed")
+            }
+        };
+
+        let mut state = serializer.serialize_struct("Error", 2)?;
+        state.serialize_field("kind", &kind)?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
@@ -0,0 +1,59 @@
+use super::Feed;
+use serde_json::{json, Value};
+
+pub const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+/// JSON Feed 1.1, empty authors are left out since the spec requires a name. Translations of a
+/// post go in the `_translations` extension.
+pub fn render(feed: &Feed) -> String {
+    let items: Vec<Value> = feed
+        .entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "id": entry.id,
+                "url": entry.url,
+                "title": entry.title,
+                "content_html": entry.html,
+                "summary": entry.summary,
+                "date_published": entry.published_at.to_rfc3339(),
+                "date_modified": entry.updated_at.to_rfc3339(),
+                "authors": entry
+                    .authors
+                    .iter()
+                    .map(|a| match &a.url {
+                        Some(url) => json!({ "name": a.name, "url": url }),
+                        None => json!({ "name": a.name }),
+                    })
+                    .collect::<Vec<Value>>(),
+                "tags": entry.tags,
+                "language": entry.locale,
+                "_translations": entry
+                    .alternates
+                    .iter()
+                    .map(|a| json!({ "language": a.locale, "url": a.url }))
+                    .collect::<Vec<Value>>(),
+            })
+        })
+        .collect();
+
+    let feed = json!({
+        "version": JSON_FEED_VERSION,
+        "title": feed.title,
+        "home_page_url": feed.home_url,
+        "feed_url": feed.feed_url,
+        "description": feed.description,
+        "authors": authors(&feed.author),
+        "language": feed.language,
+        "items": items,
+    });
+
+    feed.to_string()
+}
+
+fn authors(name: &str) -> Vec<Value> {
+    match name {
+        "" => vec![],
+        name => vec![json!({ "name": name })],
+    }
+}
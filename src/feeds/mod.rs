@@ -0,0 +1,7 @@
+mod atom;
+mod feed;
+pub mod handlers;
+mod json;
+mod rss;
+
+pub use feed::*;
@@ -0,0 +1,72 @@
+use super::Feed;
+use crate::utils::xml::escape;
+use std::fmt::Write;
+
+/// RSS 2.0, the full HTML of each post goes in `content:encoded`. The authors go in `dc:creator`,
+/// `author` would need their email.
+pub fn render(feed: &Feed) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(
+        "<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" \
+         xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n",
+    );
+    xml.push_str("<channel>\n");
+
+    let _ = writeln!(xml, "<title>{}</title>", escape(&feed.title));
+    let _ = writeln!(xml, "<link>{}</link>", escape(&feed.home_url));
+    let _ = writeln!(
+        xml,
+        "<description>{}</description>",
+        escape(&feed.description)
+    );
+    let _ = writeln!(xml, "<language>{}</language>", escape(&feed.language));
+    let _ = writeln!(
+        xml,
+        "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>",
+        escape(&feed.feed_url)
+    );
+    let _ = writeln!(
+        xml,
+        "<lastBuildDate>{}</lastBuildDate>",
+        feed.updated_at.to_rfc2822()
+    );
+
+    for entry in &feed.entries {
+        xml.push_str("<item>\n");
+        let _ = writeln!(xml, "<title>{}</title>", escape(&entry.title));
+        let _ = writeln!(xml, "<link>{}</link>", escape(&entry.url));
+        let _ = writeln!(xml, "<guid isPermaLink=\"false\">{}</guid>", entry.id);
+        for author in &entry.authors {
+            let _ = writeln!(xml, "<dc:creator>{}</dc:creator>", escape(&author.name));
+        }
+        let _ = writeln!(xml, "<dc:language>{}</dc:language>", escape(&entry.locale));
+        for alternate in &entry.alternates {
+            let _ = writeln!(
+                xml,
+                "<atom:link rel=\"alternate\" hreflang=\"{}\" href=\"{}\"/>",
+                escape(&alternate.locale),
+                escape(&alternate.url)
+            );
+        }
+        for tag in &entry.tags {
+            let _ = writeln!(xml, "<category>{}</category>", escape(tag));
+        }
+        let _ = writeln!(
+            xml,
+            "<pubDate>{}</pubDate>",
+            entry.published_at.to_rfc2822()
+        );
+        let _ = writeln!(xml, "<description>{}</description>", escape(&entry.summary));
+        let _ = writeln!(
+            xml,
+            "<content:encoded>{}</content:encoded>",
+            escape(&entry.html)
+        );
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+
+    xml
+}
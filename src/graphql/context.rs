@@ -1,4 +1,4 @@
-use crate::{authorization::jwt::AuthorizedToken, aws::build_client};
+use crate::{authorization::jwt::AuthorizedToken, aws::build_client, markdown::Renderer};
 use async_graphql::{Context, Error as AsyncGraphQLError};
 use async_mutex::{Mutex, MutexGuard};
 use aws_sdk_s3::{Client, Error as S3Error};
@@ -11,6 +11,7 @@ use std::{env, sync::Arc};
 pub struct AppContext {
     pub enforcer: Arc<Mutex<Enforcer>>,
     pub aws: AWSContext,
+    pub renderer: Renderer,
 }
 
 #[derive(Clone)]
@@ -54,6 +55,13 @@ pub async fn get_aws_from_context<'a>(
     Ok(aws)
 }
 
+pub async fn get_renderer_from_context<'a>(
+    ctx: &'a Context<'_>,
+) -> Result<&'a Renderer, AsyncGraphQLError> {
+    let AppContext { renderer, .. } = ctx.data()?;
+    Ok(renderer)
+}
+
 pub async fn get_token_from_context<'a>(
     ctx: &'a Context<'_>,
 ) -> Result<Option<&'a AuthorizedToken>, AsyncGraphQLError> {
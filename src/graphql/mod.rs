@@ -1,8 +1,12 @@
 use crate::{
     authorization::graphql::AuthorizationMutation,
-    posts::graphql::{PostsMutation, PostsQuery},
+    builds::graphql::{BuildsMutation, BuildsQuery},
+    posts::graphql::{PostsMutation, PostsQuery, PublicPostsQuery},
+    series::graphql::{SeriesMutation, SeriesQuery},
+    tags::graphql::{TagsMutation, TagsQuery},
     uploads::graphql::{UploadMutation, UploadQuery},
     user::graphql::{UserMutation, UserQuery},
+    webhooks::graphql::{WebhooksMutation, WebhooksQuery},
 };
 use async_graphql::*;
 
@@ -14,7 +18,16 @@ pub mod routes;
 pub use export_sdl::*;
 
 #[derive(MergedObject, Default)]
-pub struct QueryRoot(UserQuery, PostsQuery, UploadQuery);
+pub struct QueryRoot(
+    UserQuery,
+    PostsQuery,
+    PublicPostsQuery,
+    UploadQuery,
+    WebhooksQuery,
+    BuildsQuery,
+    SeriesQuery,
+    TagsQuery,
+);
 
 #[derive(MergedObject, Default)]
 pub struct MutationRoot(
@@ -22,6 +35,10 @@ pub struct MutationRoot(
     AuthorizationMutation,
     PostsMutation,
     UploadMutation,
+    WebhooksMutation,
+    BuildsMutation,
+    SeriesMutation,
+    TagsMutation,
 );
 
 pub type ApiSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
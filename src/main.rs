@@ -3,18 +3,26 @@ extern crate rocket;
 
 mod authorization;
 mod aws;
+mod builds;
 mod db;
+mod feeds;
 mod graphql;
+mod markdown;
 mod posts;
+mod series;
+mod sitemap;
 mod tags;
 mod uploads;
 mod user;
 mod utils;
+mod webhooks;
 
 use crate::{
     authorization::enforcer::init_enforcer,
     aws::build_client,
+    builds::coordinator::init_coordinator,
     db::{build_pool, get_figment_before_build, Db},
+    feeds::handlers::{atom_feed, json_feed, rss_feed, tag_atom_feed, tag_json_feed, tag_rss_feed},
     graphql::{
         context::{AWSContext, AppContext},
         export_sdl,
@@ -22,8 +30,15 @@ use crate::{
         routes::{graphql_playground, graphql_query, graphql_request, graphql_request_multipart},
         ApiSchema, MutationRoot, QueryRoot,
     },
+    markdown::{Renderer, RendererOptions, Sanitizer, SanitizerOptions},
+    posts::publisher::init_publisher,
+    sitemap::{
+        handlers::{get_robots, get_sitemap, get_sitemap_page},
+        RobotsConfig,
+    },
     uploads::handlers::get_file,
-    utils::cors::init_cors,
+    utils::{cors::init_cors, site::SiteConfig},
+    webhooks::dispatcher::init_dispatcher,
 };
 use async_graphql::{dataloader::DataLoader, EmptySubscription, Schema};
 use async_mutex::Mutex;
@@ -56,6 +71,10 @@ async fn main() -> Result<(), rocket::Error> {
     let context = AppContext {
         enforcer: Arc::new(Mutex::new(enforcer)),
         aws,
+        renderer: Renderer::new(
+            RendererOptions::from_env(),
+            Sanitizer::new(SanitizerOptions::from_env()),
+        ),
     };
 
     let schema: ApiSchema = Schema::build(
@@ -75,9 +94,14 @@ async fn main() -> Result<(), rocket::Error> {
         .manage(schema)
         .manage(client)
         .manage(enforcer_api)
+        .manage(SiteConfig::from_env())
+        .manage(RobotsConfig::from_env())
         .attach(Db::init())
         .attach(AdHoc::try_on_ignite("Migrations", run_migrations))
         .attach(init_cors())
+        .attach(init_publisher())
+        .attach(init_dispatcher())
+        .attach(init_coordinator())
         .mount(
             "/",
             routes![
@@ -86,6 +110,15 @@ async fn main() -> Result<(), rocket::Error> {
                 graphql_request,
                 graphql_request_multipart,
                 graphql_playground,
+                rss_feed,
+                atom_feed,
+                json_feed,
+                tag_rss_feed,
+                tag_atom_feed,
+                tag_json_feed,
+                get_sitemap,
+                get_sitemap_page,
+                get_robots,
             ],
         )
         .mount("/v1/", routes![get_file])
@@ -0,0 +1,78 @@
+use pulldown_cmark::escape::escape_html;
+use std::sync::Arc;
+use syntect::{
+    html::{ClassStyle, ClassedHTMLGenerator},
+    parsing::{SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
+};
+
+/// Tokens get one class per scope atom with this prefix, e.g. `hl-keyword hl-control`. Nothing
+/// about colors ends up in the HTML, so the site can style it with any theme.
+pub const HIGHLIGHT_CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Language {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+/// Highlights fenced code blocks on the server. Loading the syntaxes is slow, so they are shared
+/// between every clone of the highlighter.
+#[derive(Debug, Clone)]
+pub struct Highlighter {
+    syntaxes: Arc<SyntaxSet>,
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Highlighter {
+            syntaxes: Arc::new(SyntaxSet::load_defaults_newlines()),
+        }
+    }
+}
+
+impl Highlighter {
+    pub fn languages(&self) -> Vec<Language> {
+        let mut languages: Vec<Language> = self
+            .syntaxes
+            .syntaxes()
+            .iter()
+            .map(|syntax| Language {
+                name: syntax.name.clone(),
+                extensions: syntax.file_extensions.clone(),
+            })
+            .collect();
+        languages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        languages
+    }
+
+    /// Returns `None` for unknown languages, those blocks are left as plain code.
+    pub fn highlight(&self, code: &str, lang: &str) -> Option<String> {
+        let lang = lang.split_whitespace().next()?;
+        let syntax = self.find_syntax(lang)?;
+
+        let mut generator = ClassedHTMLGenerator::new_with_class_style(
+            syntax,
+            &self.syntaxes,
+            HIGHLIGHT_CLASS_STYLE,
+        );
+        for line in LinesWithEndings::from(code) {
+            generator
+                .parse_html_for_line_which_includes_newline(line)
+                .ok()?;
+        }
+
+        let mut html = String::from("<pre class=\"hl\"><code class=\"language-");
+        escape_html(&mut html, lang).ok()?;
+        html.push_str("\">");
+        html.push_str(&generator.finalize());
+        html.push_str("</code></pre>\n");
+
+        Some(html)
+    }
+
+    fn find_syntax(&self, lang: &str) -> Option<&SyntaxReference> {
+        self.syntaxes.find_syntax_by_token(lang)
+    }
+}
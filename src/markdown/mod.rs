@@ -0,0 +1,13 @@
+mod highlight;
+mod options;
+mod renderer;
+mod sanitizer;
+mod summary;
+mod toc;
+
+pub use highlight::*;
+pub use options::*;
+pub use renderer::*;
+pub use sanitizer::*;
+pub use summary::*;
+pub use toc::*;
@@ -0,0 +1,70 @@
+use pulldown_cmark::Options;
+use std::env;
+
+/// Markdown extensions on top of CommonMark, they can be turned on through the comma separated
+/// `MARKDOWN_EXTENSIONS` variable. When it's empty every extension is enabled.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RendererOptions {
+    pub tables: bool,
+    pub task_lists: bool,
+    pub strikethrough: bool,
+    pub footnotes: bool,
+    pub heading_anchors: bool,
+    pub table_of_contents: bool,
+    pub syntax_highlighting: bool,
+}
+
+impl Default for RendererOptions {
+    fn default() -> Self {
+        RendererOptions {
+            tables: true,
+            task_lists: true,
+            strikethrough: true,
+            footnotes: true,
+            heading_anchors: true,
+            table_of_contents: true,
+            syntax_highlighting: true,
+        }
+    }
+}
+
+impl RendererOptions {
+    pub fn from_env() -> Self {
+        let extensions = env::var("MARKDOWN_EXTENSIONS").unwrap_or_else(|_| String::from(""));
+        let extensions: Vec<&str> = extensions
+            .split(',')
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+            .collect();
+
+        if extensions.is_empty() {
+            return RendererOptions::default();
+        }
+
+        RendererOptions {
+            tables: extensions.contains(&"tables"),
+            task_lists: extensions.contains(&"task_lists"),
+            strikethrough: extensions.contains(&"strikethrough"),
+            footnotes: extensions.contains(&"footnotes"),
+            heading_anchors: extensions.contains(&"heading_anchors"),
+            table_of_contents: extensions.contains(&"table_of_contents"),
+            syntax_highlighting: extensions.contains(&"syntax_highlighting"),
+        }
+    }
+
+    pub fn parser_options(&self) -> Options {
+        let mut options = Options::empty();
+        options.set(Options::ENABLE_TABLES, self.tables);
+        options.set(Options::ENABLE_TASKLISTS, self.task_lists);
+        options.set(Options::ENABLE_STRIKETHROUGH, self.strikethrough);
+        options.set(Options::ENABLE_FOOTNOTES, self.footnotes);
+        options.set(Options::ENABLE_HEADING_ATTRIBUTES, self.heading_anchors);
+
+        options
+    }
+
+    /// The table of contents links to the headings, so it needs their anchors.
+    pub fn anchors_enabled(&self) -> bool {
+        self.heading_anchors || self.table_of_contents
+    }
+}
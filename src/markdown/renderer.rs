@@ -0,0 +1,143 @@
+use super::{
+    collect_headings, toc_html, Highlighter, Language, RendererOptions, Sanitizer, TocEntry,
+    TOC_PLACEHOLDER,
+};
+use pulldown_cmark::{html, CodeBlockKind, Event, Parser, Tag};
+
+/// Turns the Markdown of a post into HTML. Every post goes through the same renderer, so the
+/// options are decided once when the server starts.
+#[derive(Debug, Clone, Default)]
+pub struct Renderer {
+    options: RendererOptions,
+    highlighter: Highlighter,
+    sanitizer: Sanitizer,
+}
+
+impl Renderer {
+    pub fn new(options: RendererOptions, sanitizer: Sanitizer) -> Self {
+        Renderer {
+            options,
+            highlighter: Highlighter::default(),
+            sanitizer,
+        }
+    }
+
+    /// The HTML always goes through the sanitizer, Markdown can embed any raw HTML.
+    pub fn render(&self, raw: &str) -> String {
+        let toc = self.table_of_contents(raw);
+
+        let mut events: Vec<Event> = self.parser(raw).collect();
+
+        if self.options.anchors_enabled() {
+            let mut headings = toc.iter();
+            for event in events.iter_mut() {
+                if let Event::Start(Tag::Heading(_, id, _)) = event {
+                    *id = headings.next().map(|h| h.id.as_str());
+                }
+            }
+        }
+
+        if self.options.table_of_contents {
+            replace_toc_placeholder(&mut events, &toc);
+        }
+
+        if self.options.syntax_highlighting {
+            highlight_code_blocks(&mut events, &self.highlighter);
+        }
+
+        let mut html = String::with_capacity(raw.len() * 3 / 2);
+        html::push_html(&mut html, events.into_iter());
+
+        self.sanitize(&html)
+    }
+
+    pub fn sanitize(&self, html: &str) -> String {
+        self.sanitizer.clean(html)
+    }
+
+    pub fn table_of_contents(&self, raw: &str) -> Vec<TocEntry> {
+        if !self.options.anchors_enabled() {
+            return vec![];
+        }
+
+        collect_headings(self.parser(raw))
+    }
+
+    /// Languages fenced code blocks can be highlighted in, by name or by file extension.
+    pub fn languages(&self) -> Vec<Language> {
+        self.highlighter.languages()
+    }
+
+    fn parser<'a>(&self, raw: &'a str) -> Parser<'a, '_> {
+        Parser::new_ext(raw, self.options.parser_options())
+    }
+}
+
+/// Swaps every paragraph made only of the placeholder with the table of contents.
+fn replace_toc_placeholder(events: &mut Vec<Event>, toc: &[TocEntry]) {
+    let mut index = 0;
+
+    while index < events.len() {
+        if !matches!(events[index], Event::Start(Tag::Paragraph)) {
+            index += 1;
+            continue;
+        }
+
+        // The placeholder text may come split in several events, but nothing else can be there.
+        let mut text = String::new();
+        let mut only_text = true;
+        let mut end = index + 1;
+        while end < events.len() {
+            match &events[end] {
+                Event::Text(t) => text.push_str(t),
+                Event::End(Tag::Paragraph) => break,
+                _ => only_text = false,
+            }
+            end += 1;
+        }
+
+        if only_text && text.trim() == TOC_PLACEHOLDER && end < events.len() {
+            events.drain(index + 1..=end);
+            events[index] = Event::Html(toc_html(toc).into());
+        }
+
+        index += 1;
+    }
+}
+
+/// Swaps every fenced code block in a known language with its highlighted HTML.
+fn highlight_code_blocks(events: &mut Vec<Event>, highlighter: &Highlighter) {
+    let mut index = 0;
+
+    while index < events.len() {
+        let lang = match &events[index] {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) if !lang.is_empty() => {
+                lang.to_string()
+            }
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+
+        let mut code = String::new();
+        let mut end = index + 1;
+        while end < events.len() {
+            match &events[end] {
+                Event::Text(t) => code.push_str(t),
+                Event::End(Tag::CodeBlock(_)) => break,
+                _ => {}
+            }
+            end += 1;
+        }
+
+        if end < events.len() {
+            if let Some(html) = highlighter.highlight(&code, &lang) {
+                events.drain(index + 1..=end);
+                events[index] = Event::Html(html.into());
+            }
+        }
+
+        index += 1;
+    }
+}
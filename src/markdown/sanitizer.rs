@@ -0,0 +1,86 @@
+use ammonia::Builder;
+use std::{borrow::Cow, env};
+
+const HEADINGS: [&str; 6] = ["h1", "h2", "h3", "h4", "h5", "h6"];
+const CLASSED_TAGS: [&str; 6] = ["code", "div", "nav", "pre", "span", "sup"];
+const ALIGNMENTS: [&str; 3] = [
+    "text-align: left",
+    "text-align: center",
+    "text-align: right",
+];
+
+/// Extra tags and attributes trusted on top of the default policy, read from the comma separated
+/// `SANITIZER_EXTRA_TAGS` and `SANITIZER_EXTRA_ATTRIBUTES` variables.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SanitizerOptions {
+    pub extra_tags: Vec<String>,
+    pub extra_attributes: Vec<String>,
+}
+
+impl SanitizerOptions {
+    pub fn from_env() -> Self {
+        SanitizerOptions {
+            extra_tags: list_from_env("SANITIZER_EXTRA_TAGS"),
+            extra_attributes: list_from_env("SANITIZER_EXTRA_ATTRIBUTES"),
+        }
+    }
+}
+
+/// Allow-list of the HTML a post can contain. It starts from ammonia's defaults, which drop
+/// scripts, event handlers and `javascript:` links, and adds what the renderer produces itself:
+/// heading anchors, highlighting classes, task list checkboxes and table alignment.
+#[derive(Debug, Clone, Default)]
+pub struct Sanitizer {
+    options: SanitizerOptions,
+}
+
+impl Sanitizer {
+    pub fn new(options: SanitizerOptions) -> Self {
+        Sanitizer { options }
+    }
+
+    pub fn clean(&self, html: &str) -> String {
+        self.policy().clean(html).to_string()
+    }
+
+    fn policy(&self) -> Builder<'_> {
+        let mut policy = Builder::default();
+
+        policy
+            .add_tags(&["input"])
+            .add_tag_attributes("input", &["type", "checked", "disabled"])
+            .add_tag_attributes("div", &["id"])
+            .add_tag_attributes("th", &["style"])
+            .add_tag_attributes("td", &["style"])
+            .attribute_filter(filter_attribute)
+            .add_tags(self.options.extra_tags.iter())
+            .add_generic_attributes(self.options.extra_attributes.iter());
+
+        for tag in HEADINGS {
+            policy.add_tag_attributes(tag, &["id"]);
+        }
+        for tag in CLASSED_TAGS {
+            policy.add_tag_attributes(tag, &["class"]);
+        }
+
+        policy
+    }
+}
+
+/// Attributes with values worth checking, anything not listed here passes through untouched.
+fn filter_attribute<'u>(element: &str, attribute: &str, value: &'u str) -> Option<Cow<'u, str>> {
+    match (element, attribute) {
+        ("input", "type") => (value == "checkbox").then(|| value.into()),
+        ("th" | "td", "style") => ALIGNMENTS.contains(&value).then(|| value.into()),
+        _ => Some(value.into()),
+    }
+}
+
+fn list_from_env(key: &str) -> Vec<String> {
+    env::var(key)
+        .unwrap_or_else(|_| String::from(""))
+        .split(',')
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
@@ -0,0 +1,69 @@
+use super::TOC_PLACEHOLDER;
+use pulldown_cmark::{Event, Parser, Tag};
+
+pub const EXCERPT_MAX_CHARS: usize = 280;
+pub const WORDS_PER_MINUTE: usize = 200;
+
+/// Plain text of the first paragraph with actual content, cut at a word boundary when it's
+/// longer than `EXCERPT_MAX_CHARS`.
+pub fn derive_excerpt(raw: &str) -> String {
+    let mut text = String::new();
+    let mut in_paragraph = false;
+    let mut in_image = false;
+
+    for event in Parser::new(raw) {
+        match event {
+            Event::Start(Tag::Paragraph) => {
+                in_paragraph = true;
+                text.clear();
+            }
+            Event::End(Tag::Paragraph) => {
+                let paragraph = text.trim();
+                if !paragraph.is_empty() && paragraph != TOC_PLACEHOLDER {
+                    break;
+                }
+                in_paragraph = false;
+            }
+            Event::Start(Tag::Image(..)) => in_image = true,
+            Event::End(Tag::Image(..)) => in_image = false,
+            Event::Text(t) | Event::Code(t) if in_paragraph && !in_image => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak if in_paragraph => text.push(' '),
+            _ => {}
+        }
+    }
+
+    if !in_paragraph {
+        return String::from("");
+    }
+
+    truncate_words(text.trim(), EXCERPT_MAX_CHARS)
+}
+
+/// Words in the readable text of the post, code included.
+pub fn word_count(raw: &str) -> usize {
+    Parser::new(raw)
+        .map(|event| match event {
+            Event::Text(t) | Event::Code(t) => t.split_whitespace().count(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Rounded up, so any post with content takes at least a minute.
+pub fn reading_time_minutes(words: usize) -> usize {
+    words / WORDS_PER_MINUTE + usize::from(words % WORDS_PER_MINUTE != 0)
+}
+
+fn truncate_words(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let cut: String = text.chars().take(max_chars).collect();
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(index) => &cut[..index],
+        None => &cut,
+    };
+
+    format!("{}…", cut.trim_end_matches(|c: char| !c.is_alphanumeric()))
+}
@@ -0,0 +1,95 @@
+use crate::utils::slug::{slugify, with_suffix};
+use pulldown_cmark::{escape::escape_html, Event, Parser, Tag};
+use std::collections::HashSet;
+
+/// Paragraph that gets replaced with the table of contents when it is enabled.
+pub const TOC_PLACEHOLDER: &str = "[TOC]";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TocEntry {
+    pub level: u32,
+    pub id: String,
+    pub title: String,
+}
+
+/// Collects the headings of a document with a unique id for each one. An id given through the
+/// `{#id}` heading attribute is kept, otherwise it's derived from the heading text.
+pub fn collect_headings(parser: Parser<'_, '_>) -> Vec<TocEntry> {
+    let mut entries: Vec<TocEntry> = vec![];
+    let mut used: HashSet<String> = HashSet::new();
+    let mut current: Option<(u32, Option<String>, String)> = None;
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::Heading(level, id, _)) => {
+                current = Some((level as u32, id.map(String::from), String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, title)) = current.as_mut() {
+                    title.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                let (level, id, title) = match current.take() {
+                    Some(heading) => heading,
+                    None => continue,
+                };
+
+                let base = match id.unwrap_or_else(|| slugify(&title)) {
+                    id if id.is_empty() => String::from("section"),
+                    id => id,
+                };
+
+                let mut suffix = 1;
+                let mut id = base.clone();
+                while used.contains(&id) {
+                    suffix += 1;
+                    id = with_suffix(&base, suffix);
+                }
+                used.insert(id.clone());
+
+                entries.push(TocEntry {
+                    level,
+                    id,
+                    title: title.trim().to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    entries
+}
+
+/// Nested list of links to every heading, indented relative to the top most heading.
+pub fn toc_html(entries: &[TocEntry]) -> String {
+    let top = entries.iter().map(|e| e.level).min().unwrap_or(1);
+    let mut html = String::from("<nav class=\"toc\">\n");
+    let mut depth = 0;
+
+    for entry in entries {
+        let level = entry.level - top + 1;
+        while depth < level {
+            html.push_str("<ul>\n");
+            depth += 1;
+        }
+        while depth > level {
+            html.push_str("</ul>\n");
+            depth -= 1;
+        }
+
+        html.push_str("<li><a href=\"#");
+        let _ = escape_html(&mut html, &entry.id);
+        html.push_str("\">");
+        let _ = escape_html(&mut html, &entry.title);
+        html.push_str("</a></li>\n");
+    }
+
+    while depth > 0 {
+        html.push_str("</ul>\n");
+        depth -= 1;
+    }
+    html.push_str("</nav>\n");
+
+    html
+}
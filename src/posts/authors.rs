@@ -0,0 +1,274 @@
+use crate::posts::graphql::PostAuthorUuid;
+use entity::{
+    enums::CreditRole,
+    post_authors::{self, Entity as PostAuthor},
+    posts::{self, Entity as Post},
+    users::{self, Entity as User},
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+/// Someone credited on a post and what they did on it.
+#[derive(Clone, Debug)]
+pub struct Credit {
+    pub user: Uuid,
+    pub role: CreditRole,
+}
+
+pub struct PostAuthorsRepository;
+
+impl PostAuthorsRepository {
+    /// Credits the creator of a new post as its author.
+    pub async fn record_creator<C>(conn: &C, post: &posts::Model) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let credit = post_authors::ActiveModel {
+            post_uuid: Set(post.uuid.clone()),
+            user_uuid: Set(post.created_by.clone()),
+            role: Set(CreditRole::Author.to_string()),
+            position: Set(0),
+            ..Default::default()
+        };
+
+        PostAuthor::insert(credit)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Replaces the credits of a post, they are listed in the given order.
+    pub async fn set(
+        conn: &DatabaseConnection,
+        post_uuid: Uuid,
+        credits: Vec<Credit>,
+    ) -> Result<Vec<post_authors::Model>> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        PostAuthorsRepository::ensure_post(&txn, post_uuid).await?;
+        ensure!(
+            credits.iter().any(|c| c.role == CreditRole::Author),
+            MissingAuthorSnafu
+        );
+
+        let mut credited: Vec<Uuid> = Vec::with_capacity(credits.len());
+        for credit in credits.iter() {
+            ensure!(
+                !credited.contains(&credit.user),
+                DuplicateCreditSnafu { user: credit.user }
+            );
+            credited.push(credit.user);
+        }
+
+        let ids: Vec<Vec<u8>> = credited.iter().map(|u| u.as_bytes().to_vec()).collect();
+        let existing = User::find()
+            .filter(users::Column::Uuid.is_in(ids))
+            .count(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+        ensure!(existing == credited.len(), MissingUsersSnafu);
+
+        PostAuthor::delete_many()
+            .filter(post_authors::Column::PostUuid.eq(post_uuid.as_bytes().to_vec()))
+            .exec(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let credits: Vec<post_authors::ActiveModel> = credits
+            .into_iter()
+            .enumerate()
+            .map(|(position, credit)| post_authors::ActiveModel {
+                post_uuid: Set(post_uuid.as_bytes().to_vec()),
+                user_uuid: Set(credit.user.as_bytes().to_vec()),
+                role: Set(credit.role.to_string()),
+                position: Set(position as i32),
+                ..Default::default()
+            })
+            .collect();
+
+        PostAuthor::insert_many(credits)
+            .exec(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let credits = PostAuthorsRepository::find_by_post(&txn, post_uuid).await?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(credits)
+    }
+
+    /// Credits someone on a post, after the existing credits. Someone already credited only
+    /// gets their role changed.
+    pub async fn add(
+        conn: &DatabaseConnection,
+        post_uuid: Uuid,
+        credit: Credit,
+    ) -> Result<Vec<post_authors::Model>> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let mut credits = PostAuthorsRepository::find_by_post(&txn, post_uuid).await?;
+
+        let existing = credits
+            .iter()
+            .position(|c| c.user_uuid() == *credit.user.as_bytes());
+
+        match existing {
+            Some(index) => {
+                let other_author = credits
+                    .iter()
+                    .enumerate()
+                    .any(|(i, c)| i != index && is_author(c));
+                ensure!(
+                    other_author || credit.role == CreditRole::Author,
+                    MissingAuthorSnafu
+                );
+
+                let mut updated: post_authors::ActiveModel = credits.remove(index).into();
+                updated.role = Set(credit.role.to_string());
+                updated.update(&txn).await.context(QueryFailedSnafu)?;
+            }
+            None => {
+                PostAuthorsRepository::ensure_post(&txn, post_uuid).await?;
+
+                let user = User::find_by_id(credit.user.as_bytes().to_vec())
+                    .one(&txn)
+                    .await
+                    .context(QueryFailedSnafu)?;
+                ensure!(user.is_some(), MissingUsersSnafu);
+
+                let position = credits.iter().map(|c| c.position + 1).max().unwrap_or(0);
+                let added = post_authors::ActiveModel {
+                    post_uuid: Set(post_uuid.as_bytes().to_vec()),
+                    user_uuid: Set(credit.user.as_bytes().to_vec()),
+                    role: Set(credit.role.to_string()),
+                    position: Set(position),
+                    ..Default::default()
+                };
+
+                PostAuthor::insert(added)
+                    .exec(&txn)
+                    .await
+                    .context(QueryFailedSnafu)?;
+            }
+        };
+
+        let credits = PostAuthorsRepository::find_by_post(&txn, post_uuid).await?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(credits)
+    }
+
+    /// Takes someone off the credits, a post always keeps at least one author.
+    pub async fn remove(
+        conn: &DatabaseConnection,
+        post_uuid: Uuid,
+        user: Uuid,
+    ) -> Result<Vec<post_authors::Model>> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let credits = PostAuthorsRepository::find_by_post(&txn, post_uuid).await?;
+
+        let (removed, remaining): (Vec<_>, Vec<_>) = credits
+            .into_iter()
+            .partition(|c| c.user_uuid() == *user.as_bytes());
+        let removed = removed
+            .into_iter()
+            .next()
+            .context(CreditNotFoundSnafu { user })?;
+        ensure!(remaining.iter().any(is_author), MissingAuthorSnafu);
+
+        PostAuthor::delete_by_id(removed.uuid)
+            .exec(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(remaining)
+    }
+
+    pub async fn find_by_post<C>(conn: &C, post_uuid: Uuid) -> Result<Vec<post_authors::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        PostAuthor::find()
+            .filter(post_authors::Column::PostUuid.eq(post_uuid.as_bytes().to_vec()))
+            .order_by_asc(post_authors::Column::Position)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Used for the authors dataloader. It returns the credits grouped by post, in order.
+    pub async fn find_by_post_ids(
+        conn: &DatabaseConnection,
+        ids: &[PostAuthorUuid],
+    ) -> Result<Vec<(Uuid, Vec<post_authors::Model>)>> {
+        let ids_vec: Vec<Vec<u8>> = ids.iter().map(|id| id.0.as_bytes().to_vec()).collect();
+
+        let credits = PostAuthor::find()
+            .filter(post_authors::Column::PostUuid.is_in(ids_vec))
+            .order_by_asc(post_authors::Column::Position)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let mut grouped: Vec<(Uuid, Vec<post_authors::Model>)> = vec![];
+        for credit in credits {
+            let post_uuid = Uuid::from_bytes(credit.post_uuid());
+
+            match grouped.iter().position(|(uuid, _)| uuid == &post_uuid) {
+                Some(i) => grouped[i].1.push(credit),
+                None => grouped.push((post_uuid, vec![credit])),
+            }
+        }
+
+        Ok(grouped)
+    }
+
+    async fn ensure_post<C>(conn: &C, post_uuid: Uuid) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let post = Post::find_by_id(post_uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+        ensure!(post.is_some(), PostNotFoundSnafu { uuid: post_uuid });
+
+        Ok(())
+    }
+}
+
+fn is_author(credit: &post_authors::Model) -> bool {
+    credit.role == CreditRole::Author.to_string()
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Post Authors Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Post with uuid {} not found", uuid))]
+    PostNotFound { uuid: Uuid },
+
+    #[snafu(display("Some of the credited users don't exist"))]
+    MissingUsers,
+
+    #[snafu(display("User {} is credited more than once", user))]
+    DuplicateCredit { user: Uuid },
+
+    #[snafu(display("User {} is not credited on the post", user))]
+    CreditNotFound { user: Uuid },
+
+    #[snafu(display("A post needs at least one author"))]
+    MissingAuthor,
+}
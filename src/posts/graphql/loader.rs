@@ -0,0 +1,72 @@
+use super::PostCredit;
+use crate::{
+    graphql::loader::DataLoader,
+    posts::{AuthorsError, PostAuthorsRepository},
+};
+use async_graphql::{dataloader::Loader, Result};
+use snafu::prelude::*;
+use std::{
+    cmp::{Eq, PartialEq},
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+use uuid::Uuid;
+
+#[async_trait]
+impl Loader<PostAuthorUuid> for DataLoader {
+    type Value = Vec<PostCredit>;
+    type Error = Arc<Error>;
+
+    async fn load(
+        &self,
+        uuids: &[PostAuthorUuid],
+    ) -> Result<HashMap<PostAuthorUuid, Self::Value>, Self::Error> {
+        let credits = PostAuthorsRepository::find_by_post_ids(&self.pool.conn, uuids)
+            .await
+            .context(QuerySnafu)?;
+
+        let mut grouped: HashMap<PostAuthorUuid, Self::Value> = HashMap::new();
+        for (post_uuid, credits) in credits.into_iter() {
+            let credits = credits
+                .into_iter()
+                .map(PostCredit::try_from)
+                .collect::<Result<Vec<PostCredit>, _>>()
+                .context(InvalidCreditSnafu)?;
+
+            grouped.insert(PostAuthorUuid::new(post_uuid), credits);
+        }
+
+        Ok(grouped)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("{}", source))]
+    QueryError { source: AuthorsError },
+
+    #[snafu(display("{}", source))]
+    InvalidCredit { source: super::Error },
+}
+
+#[derive(Clone, Eq)]
+pub struct PostAuthorUuid(pub Uuid);
+
+impl PostAuthorUuid {
+    pub fn new(uuid: Uuid) -> Self {
+        PostAuthorUuid(uuid)
+    }
+}
+
+impl PartialEq for PostAuthorUuid {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Hash for PostAuthorUuid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
@@ -1,34 +1,71 @@
 use crate::{
-    graphql::loader::DataLoader as AppLoader,
+    graphql::{
+        context::{get_conn_from_context, get_renderer_from_context},
+        loader::DataLoader as AppLoader,
+    },
+    markdown::{
+        derive_excerpt, reading_time_minutes, word_count, Language, TocEntry as MarkdownTocEntry,
+    },
+    posts::{PostTranslationsRepository, RelatedPostsRepository, SearchHit},
+    series::{graphql::PostSeries, SeriesRepository},
     tags::graphql::{PostTagUuid, Tag},
     user::graphql::{User, UserUuid},
+    utils::{diff::LineChange, locale::normalize_locale},
 };
 use async_graphql::{
     dataloader::DataLoader, ComplexObject, Context, Enum, Result, SimpleObject, ID,
 };
 use chrono::{DateTime, Utc};
-use entity::{enums::Status as DBStatus, posts};
+use entity::{
+    enums::{CreditRole as DBCreditRole, Status as DBStatus},
+    post_authors, post_redirects, post_revisions, posts,
+};
 use snafu::prelude::*;
 use std::{convert::From, str::FromStr};
 use strum::ParseError;
 use strum_macros::{Display, EnumString};
 use uuid::Uuid;
 
+mod loader;
 mod mutation;
+mod public;
 mod query;
 
+pub use loader::*;
 pub use mutation::*;
+pub use public::*;
 pub use query::*;
 
+/// Related posts listed when no limit is given.
+const DEFAULT_RELATED: u64 = 5;
+
+/// Locale arguments accept the same spellings as the posts, e.g. `pt_br` finds `pt-BR`.
+fn parse_locale(locale: Option<String>) -> Result<Option<String>, Error> {
+    match locale {
+        Some(raw) => {
+            let locale = normalize_locale(&raw).context(InvalidLocaleSnafu { locale: raw })?;
+            Ok(Some(locale))
+        }
+        None => Ok(None),
+    }
+}
+
 #[derive(SimpleObject, Clone, Debug)]
 #[graphql(complex)]
 pub struct Post {
     pub uuid: ID,
+    pub title: String,
+    pub slug: String,
     pub status: Status,
     pub raw: String,
     pub html: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub locale: String,
+    #[graphql(skip)]
+    custom_excerpt: Option<String>,
     #[graphql(skip)]
     created_by: String,
 }
@@ -44,6 +81,15 @@ impl Post {
         Ok(author)
     }
 
+    /// Everyone credited on the post, in the order they are listed.
+    async fn authors(&self, ctx: &Context<'_>) -> Result<Vec<PostCredit>> {
+        let loader = ctx.data_unchecked::<DataLoader<AppLoader>>();
+        let uuid = Uuid::from_str(&self.uuid)?;
+        let credits = loader.load_one(PostAuthorUuid::new(uuid)).await?;
+
+        Ok(credits.unwrap_or_default())
+    }
+
     async fn tags(&self, ctx: &Context<'_>) -> Result<Vec<Tag>> {
         let loader = ctx.data_unchecked::<DataLoader<AppLoader>>();
         let uuid = Uuid::from_str(&self.uuid)?;
@@ -55,6 +101,284 @@ impl Post {
 
         Ok(tags)
     }
+
+    /// The excerpt given by the author, otherwise the start of the first paragraph.
+    async fn excerpt(&self) -> String {
+        match &self.custom_excerpt {
+            Some(excerpt) => excerpt.clone(),
+            None => derive_excerpt(&self.raw),
+        }
+    }
+
+    async fn word_count(&self) -> i32 {
+        word_count(&self.raw) as i32
+    }
+
+    async fn reading_time_minutes(&self) -> i32 {
+        reading_time_minutes(word_count(&self.raw)) as i32
+    }
+
+    /// Headings of the post, in document order.
+    async fn toc(&self, ctx: &Context<'_>) -> Result<Vec<TocEntry>> {
+        let renderer = get_renderer_from_context(ctx).await?;
+        let toc = renderer
+            .table_of_contents(&self.raw)
+            .into_iter()
+            .map(TocEntry::from)
+            .collect();
+
+        Ok(toc)
+    }
+
+    /// The same post in other languages, whatever their status.
+    async fn translations(&self, ctx: &Context<'_>) -> Result<Vec<Post>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        let posts = PostTranslationsRepository::find_for(conn, uuid, None)
+            .await?
+            .into_iter()
+            .map(Post::try_from)
+            .collect::<Result<Vec<Post>, _>>()?;
+
+        Ok(posts)
+    }
+
+    /// Other published posts sharing tags or content with this one, most related first.
+    async fn related_posts(&self, ctx: &Context<'_>, limit: Option<u64>) -> Result<Vec<Post>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+        let limit = limit.unwrap_or(DEFAULT_RELATED);
+
+        let posts = RelatedPostsRepository::find_for(conn, uuid, limit)
+            .await?
+            .into_iter()
+            .map(Post::try_from)
+            .collect::<Result<Vec<Post>, _>>()?;
+
+        Ok(posts)
+    }
+
+    /// The series this post is part of, with the previous and next parts.
+    async fn series(&self, ctx: &Context<'_>) -> Result<Option<PostSeries>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        match SeriesRepository::find_by_post(conn, uuid).await? {
+            Some((series, parts)) => PostSeries::new(series, parts, uuid),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Someone credited on a post.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct PostCredit {
+    pub role: CreditRole,
+    pub position: i32,
+    #[graphql(skip)]
+    pub user_uuid: Uuid,
+}
+
+#[ComplexObject]
+impl PostCredit {
+    async fn user(&self, ctx: &Context<'_>) -> Result<User> {
+        let loader = ctx.data_unchecked::<DataLoader<AppLoader>>();
+        let user = loader
+            .load_one(UserUuid::new(self.user_uuid))
+            .await?
+            .context(InvalidAuthorSnafu)?;
+
+        Ok(user)
+    }
+}
+
+impl TryFrom<post_authors::Model> for PostCredit {
+    type Error = Error;
+
+    fn try_from(credit: post_authors::Model) -> Result<Self, Self::Error> {
+        let uuid = Uuid::from_bytes(credit.uuid());
+        let role = DBCreditRole::from_str(&credit.role).context(InvalidCreditRoleSnafu { uuid })?;
+
+        Ok(PostCredit {
+            role: role.into(),
+            position: credit.position,
+            user_uuid: Uuid::from_bytes(credit.user_uuid()),
+        })
+    }
+}
+
+#[derive(Enum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CreditRole {
+    Author,
+    CoAuthor,
+    Editor,
+    Reviewer,
+}
+
+impl From<CreditRole> for DBCreditRole {
+    fn from(role: CreditRole) -> Self {
+        match role {
+            CreditRole::Author => DBCreditRole::Author,
+            CreditRole::CoAuthor => DBCreditRole::CoAuthor,
+            CreditRole::Editor => DBCreditRole::Editor,
+            CreditRole::Reviewer => DBCreditRole::Reviewer,
+        }
+    }
+}
+
+impl From<DBCreditRole> for CreditRole {
+    fn from(role: DBCreditRole) -> Self {
+        match role {
+            DBCreditRole::Author => CreditRole::Author,
+            DBCreditRole::CoAuthor => CreditRole::CoAuthor,
+            DBCreditRole::Editor => CreditRole::Editor,
+            DBCreditRole::Reviewer => CreditRole::Reviewer,
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct TocEntry {
+    pub level: i32,
+    pub id: String,
+    pub title: String,
+}
+
+impl From<MarkdownTocEntry> for TocEntry {
+    fn from(entry: MarkdownTocEntry) -> Self {
+        TocEntry {
+            level: entry.level as i32,
+            id: entry.id,
+            title: entry.title,
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct HighlightLanguage {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+impl From<Language> for HighlightLanguage {
+    fn from(language: Language) -> Self {
+        HighlightLanguage {
+            name: language.name,
+            extensions: language.extensions,
+        }
+    }
+}
+
+/// A post found by a search, with the matched terms wrapped in `<mark>` tags.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct PostSearchResult {
+    pub post: Post,
+    /// Title of the post with its matches highlighted.
+    pub title: String,
+    /// The part of the Markdown that matched best, escaped and highlighted.
+    pub snippet: String,
+    /// bm25 score of the match, lower is more relevant.
+    pub rank: f64,
+}
+
+impl TryFrom<SearchHit> for PostSearchResult {
+    type Error = Error;
+
+    fn try_from(hit: SearchHit) -> Result<Self, Self::Error> {
+        Ok(PostSearchResult {
+            post: hit.post.try_into()?,
+            title: hit.title,
+            snippet: hit.snippet,
+            rank: hit.rank,
+        })
+    }
+}
+
+/// Maps a slug a post used to have to the slug it has now.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct Redirect {
+    pub from: String,
+    pub to: String,
+    pub post_uuid: ID,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<(post_redirects::Model, posts::Model)> for Redirect {
+    fn from((redirect, post): (post_redirects::Model, posts::Model)) -> Self {
+        let post_uuid = Uuid::from_bytes(post.uuid());
+
+        Redirect {
+            from: redirect.slug,
+            to: post.slug,
+            post_uuid: post_uuid.into(),
+            created_at: redirect.created_at,
+        }
+    }
+}
+
+/// A snapshot of a post, one gets written every time the post is saved.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct Revision {
+    pub uuid: ID,
+    pub post_uuid: ID,
+    pub title: String,
+    pub raw: String,
+    pub created_at: DateTime<Utc>,
+    #[graphql(skip)]
+    created_by: Uuid,
+}
+
+#[ComplexObject]
+impl Revision {
+    async fn author(&self, ctx: &Context<'_>) -> Result<User> {
+        let loader = ctx.data_unchecked::<DataLoader<AppLoader>>();
+        let uuid = UserUuid::new(self.created_by);
+        let author = loader.load_one(uuid).await?.context(InvalidAuthorSnafu)?;
+
+        Ok(author)
+    }
+}
+
+impl From<post_revisions::Model> for Revision {
+    fn from(revision: post_revisions::Model) -> Self {
+        let uuid = Uuid::from_bytes(revision.uuid());
+        let post_uuid = Uuid::from_bytes(revision.post_uuid());
+
+        Revision {
+            uuid: uuid.into(),
+            post_uuid: post_uuid.into(),
+            title: revision.title,
+            raw: revision.raw,
+            created_at: revision.created_at,
+            created_by: Uuid::from_bytes(revision.created_by()),
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct DiffLine {
+    pub change: DiffChange,
+    pub line: String,
+}
+
+#[derive(Enum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiffChange {
+    Added,
+    Removed,
+    Unchanged,
+}
+
+impl From<LineChange> for DiffChange {
+    fn from(change: LineChange) -> Self {
+        match change {
+            LineChange::Added => DiffChange::Added,
+            LineChange::Removed => DiffChange::Removed,
+            LineChange::Unchanged => DiffChange::Unchanged,
+        }
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -76,11 +400,17 @@ impl TryFrom<posts::Model> for Post {
 
         Ok(Post {
             uuid: uuid.into(),
+            title: post.title,
+            slug: post.slug,
             status,
             html: post.html,
             raw: post.raw,
             created_at: post.created_at,
             updated_at: post.updated_at,
+            publish_at: post.publish_at,
+            published_at: post.published_at,
+            locale: post.locale,
+            custom_excerpt: post.excerpt,
             created_by: created_by.to_string(),
         })
     }
@@ -91,6 +421,7 @@ pub enum Status {
     Published,
     Disabled,
     Draft,
+    Scheduled,
 }
 
 impl From<Status> for DBStatus {
@@ -99,6 +430,7 @@ impl From<Status> for DBStatus {
             Status::Published => DBStatus::Published,
             Status::Disabled => DBStatus::Disabled,
             Status::Draft => DBStatus::Draft,
+            Status::Scheduled => DBStatus::Scheduled,
         }
     }
 }
@@ -109,6 +441,7 @@ impl From<DBStatus> for Status {
             DBStatus::Published => Status::Published,
             DBStatus::Disabled => Status::Disabled,
             DBStatus::Draft => Status::Draft,
+            DBStatus::Scheduled => Status::Scheduled,
         }
     }
 }
@@ -117,4 +450,10 @@ impl From<DBStatus> for Status {
 pub enum Error {
     #[snafu(display("Status: {} is not valid for Post: {}", source, uuid))]
     InvalidStatus { source: ParseError, uuid: Uuid },
+
+    #[snafu(display("Role: {} is not valid for Credit: {}", source, uuid))]
+    InvalidCreditRole { source: ParseError, uuid: Uuid },
+
+    #[snafu(display("Locale '{}' is not valid", locale))]
+    InvalidLocale { locale: String },
 }
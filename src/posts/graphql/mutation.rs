@@ -1,36 +1,73 @@
-use super::{Post, Status};
+use super::{CreditRole, Post, PostCredit, Status};
 use crate::{
     authorization::graphql::{Action, Resource, RoleGuard},
-    graphql::context::{get_conn_from_context, get_token_from_context},
+    builds::coordinator::notify_change,
+    graphql::context::{get_conn_from_context, get_renderer_from_context, get_token_from_context},
     posts::{
-        ChangePostStatusInput as ChangePostStatusRepoInput, NewPostInput as NewPostRepoInput,
-        PostsRepository, UpdatePostInput as UpdatePostRepoInput,
+        ChangePostStatusInput as ChangePostStatusRepoInput, Credit,
+        NewPostInput as NewPostRepoInput, PostAuthorsRepository, PostRevisionsRepository,
+        PostTranslationsRepository, PostsRepository, UpdatePostInput as UpdatePostRepoInput,
     },
+    tags::{graphql::PostTagUuid, TagsRepository},
+    utils::locale::default_locale,
 };
 use async_graphql::{Context, InputObject, Object, Result as GraphqlResult, ID};
-use markdown_to_html::markdown;
+use chrono::{DateTime, Utc};
+use entity::post_authors;
+use snafu::prelude::*;
 use std::str::FromStr;
 use uuid::Uuid;
 
 #[derive(InputObject)]
 pub struct NewPostInput {
     pub title: String,
+    pub slug: Option<String>,
     pub raw: String,
+    /// Summary for index pages, derived from the first paragraph when left empty.
+    pub excerpt: Option<String>,
     pub tags: Vec<String>,
+    /// Language tag like `es` or `pt-BR`, the site locale when left empty.
+    pub locale: Option<String>,
+    /// Links the new post to the translations of another one.
+    pub translation_of: Option<ID>,
 }
 
 #[derive(InputObject)]
 pub struct UpdatePostInput {
     pub uuid: ID,
     pub title: String,
+    pub slug: Option<String>,
     pub raw: String,
+    /// Keeps the current excerpt when left out, an empty one goes back to the first paragraph.
+    pub excerpt: Option<String>,
     pub tags: Vec<String>,
+    /// Keeps the current locale when left empty.
+    pub locale: Option<String>,
 }
 
 #[derive(InputObject)]
 pub struct ChangePostStatusInput {
     pub uuid: ID,
     pub status: Status,
+    /// Required when scheduling a post, the publisher makes it public once this date is reached.
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+#[derive(InputObject)]
+pub struct CreditInput {
+    pub user_uuid: ID,
+    pub role: CreditRole,
+}
+
+impl TryFrom<CreditInput> for Credit {
+    type Error = uuid::Error;
+
+    fn try_from(input: CreditInput) -> Result<Self, Self::Error> {
+        Ok(Credit {
+            user: Uuid::from_str(&input.user_uuid)?,
+            role: input.role.into(),
+        })
+    }
 }
 
 #[derive(Default)]
@@ -41,19 +78,30 @@ impl PostsMutation {
     #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Write)")]
     pub async fn new_post(&self, ctx: &Context<'_>, input: NewPostInput) -> GraphqlResult<Post> {
         let conn = get_conn_from_context(ctx).await?;
+        let renderer = get_renderer_from_context(ctx).await?;
         let token = get_token_from_context(ctx).await?.expect("Missing Token");
         let uuid = token.uuid;
 
+        let translation_of = match input.translation_of {
+            Some(id) => Some(Uuid::from_str(&id)?),
+            None => None,
+        };
+
         let input = NewPostRepoInput {
             created_by: uuid,
-            html: markdown(&input.raw),
+            html: renderer.render(&input.raw),
             raw: input.raw,
+            excerpt: input.excerpt,
             title: input.title,
+            slug: input.slug,
             tags: input.tags,
+            locale: input.locale.unwrap_or_else(default_locale),
+            translation_of,
         };
 
         let post = PostsRepository::create(conn, input).await?;
         let post: Post = post.try_into()?;
+        notify_change(conn, "Post created").await;
 
         Ok(post)
     }
@@ -65,18 +113,25 @@ impl PostsMutation {
         input: UpdatePostInput,
     ) -> GraphqlResult<Post> {
         let conn = get_conn_from_context(ctx).await?;
+        let renderer = get_renderer_from_context(ctx).await?;
+        let token = get_token_from_context(ctx).await?.expect("Missing Token");
         let uuid = Uuid::from_str(&input.uuid)?;
 
         let input = UpdatePostRepoInput {
             uuid,
-            html: markdown(&input.raw),
+            html: renderer.render(&input.raw),
             raw: input.raw,
+            excerpt: input.excerpt,
             title: input.title,
+            slug: input.slug,
             tags: input.tags,
+            locale: input.locale,
+            updated_by: token.uuid,
         };
 
         let post = PostsRepository::update_post(conn, input).await?;
         let post: Post = post.try_into()?;
+        notify_change(conn, "Post updated").await;
 
         Ok(post)
     }
@@ -93,21 +148,188 @@ impl PostsMutation {
         let input = ChangePostStatusRepoInput {
             uuid,
             status: input.status.into(),
+            publish_at: input.publish_at,
         };
 
         let post = PostsRepository::change_post_status(conn, input).await?;
         let post: Post = post.try_into()?;
+        notify_change(conn, "Post status changed").await;
+
+        Ok(post)
+    }
+
+    /// Brings back the title and content of a revision. The restore is saved as a new revision,
+    /// so the history in between is kept.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Write)")]
+    pub async fn restore_revision(&self, ctx: &Context<'_>, uuid: String) -> GraphqlResult<Post> {
+        let conn = get_conn_from_context(ctx).await?;
+        let renderer = get_renderer_from_context(ctx).await?;
+        let token = get_token_from_context(ctx).await?.expect("Missing Token");
+        let uuid = Uuid::from_str(&uuid)?;
+
+        let revision = PostRevisionsRepository::find_by_id(conn, uuid)
+            .await?
+            .context(MissingRevisionSnafu { uuid })?;
+        let post_uuid = Uuid::from_bytes(revision.post_uuid());
+
+        // Tags and the excerpt are not part of the history, the post keeps the ones it has now.
+        let tags = TagsRepository::find_by_post_ids(conn, &[PostTagUuid::new(post_uuid)])
+            .await?
+            .into_iter()
+            .flat_map(|(_, tags)| tags)
+            .map(|tag| tag.name)
+            .collect();
+
+        let input = UpdatePostRepoInput {
+            uuid: post_uuid,
+            html: renderer.render(&revision.raw),
+            raw: revision.raw,
+            excerpt: None,
+            title: revision.title,
+            slug: None,
+            tags,
+            locale: None,
+            updated_by: token.uuid,
+        };
+
+        let post = PostsRepository::update_post(conn, input).await?;
+        let post: Post = post.try_into()?;
+        notify_change(conn, "Revision restored").await;
 
         Ok(post)
     }
 
+    /// Runs every stored post through the current sanitizer policy, returns how many changed.
+    #[graphql(guard = "RoleGuard::new(Resource::Sanitizer, Action::Write)")]
+    pub async fn sanitize_posts(&self, ctx: &Context<'_>) -> GraphqlResult<i32> {
+        let conn = get_conn_from_context(ctx).await?;
+        let renderer = get_renderer_from_context(ctx).await?;
+
+        let sanitized = PostsRepository::rewrite_html(conn, |html| renderer.sanitize(html)).await?;
+        if sanitized > 0 {
+            notify_change(conn, "Posts sanitized").await;
+        }
+
+        Ok(sanitized as i32)
+    }
+
     #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Write)")]
     pub async fn delete_post(&self, ctx: &Context<'_>, uuid: String) -> GraphqlResult<ID> {
         let conn = get_conn_from_context(ctx).await?;
         let uuid = Uuid::from_str(&uuid)?;
 
         PostsRepository::delete(conn, uuid).await?;
+        notify_change(conn, "Post deleted").await;
 
         Ok(uuid.into())
     }
+
+    /// Makes a post a translation of another one, it leaves any translations it had before.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Write)")]
+    pub async fn link_translation(
+        &self,
+        ctx: &Context<'_>,
+        post_uuid: String,
+        translation_of: String,
+    ) -> GraphqlResult<Vec<Post>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let post_uuid = Uuid::from_str(&post_uuid)?;
+        let translation_of = Uuid::from_str(&translation_of)?;
+
+        let posts = PostTranslationsRepository::link(conn, post_uuid, translation_of)
+            .await?
+            .into_iter()
+            .map(Post::try_from)
+            .collect::<Result<Vec<Post>, _>>()?;
+        notify_change(conn, "Translations changed").await;
+
+        Ok(posts)
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Write)")]
+    pub async fn unlink_translation(
+        &self,
+        ctx: &Context<'_>,
+        post_uuid: String,
+    ) -> GraphqlResult<Post> {
+        let conn = get_conn_from_context(ctx).await?;
+        let post_uuid = Uuid::from_str(&post_uuid)?;
+
+        let post = PostTranslationsRepository::unlink(conn, post_uuid).await?;
+        let post: Post = post.try_into()?;
+        notify_change(conn, "Translations changed").await;
+
+        Ok(post)
+    }
+
+    /// Replaces everyone credited on a post, they are listed in the given order. At least one
+    /// of them has to be an author.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Write)")]
+    pub async fn set_post_credits(
+        &self,
+        ctx: &Context<'_>,
+        post_uuid: String,
+        credits: Vec<CreditInput>,
+    ) -> GraphqlResult<Vec<PostCredit>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let post_uuid = Uuid::from_str(&post_uuid)?;
+        let credits = credits
+            .into_iter()
+            .map(Credit::try_from)
+            .collect::<Result<Vec<Credit>, _>>()?;
+
+        let credits = PostAuthorsRepository::set(conn, post_uuid, credits).await?;
+        notify_change(conn, "Post credits changed").await;
+
+        into_credits(credits)
+    }
+
+    /// Credits someone at the end of the list, or changes their role when they already are.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Write)")]
+    pub async fn add_post_credit(
+        &self,
+        ctx: &Context<'_>,
+        post_uuid: String,
+        input: CreditInput,
+    ) -> GraphqlResult<Vec<PostCredit>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let post_uuid = Uuid::from_str(&post_uuid)?;
+
+        let credits = PostAuthorsRepository::add(conn, post_uuid, input.try_into()?).await?;
+        notify_change(conn, "Post credits changed").await;
+
+        into_credits(credits)
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Write)")]
+    pub async fn remove_post_credit(
+        &self,
+        ctx: &Context<'_>,
+        post_uuid: String,
+        user_uuid: String,
+    ) -> GraphqlResult<Vec<PostCredit>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let post_uuid = Uuid::from_str(&post_uuid)?;
+        let user_uuid = Uuid::from_str(&user_uuid)?;
+
+        let credits = PostAuthorsRepository::remove(conn, post_uuid, user_uuid).await?;
+        notify_change(conn, "Post credits changed").await;
+
+        into_credits(credits)
+    }
+}
+
+fn into_credits(credits: Vec<post_authors::Model>) -> GraphqlResult<Vec<PostCredit>> {
+    let credits = credits
+        .into_iter()
+        .map(PostCredit::try_from)
+        .collect::<Result<Vec<PostCredit>, _>>()?;
+
+    Ok(credits)
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Revision does not exist with uuid {}", uuid))]
+    MissingRevision { uuid: Uuid },
 }
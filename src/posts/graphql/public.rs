@@ -0,0 +1,490 @@
+use super::{parse_locale, CreditRole, PostAuthorUuid, PostsOrder, DEFAULT_RELATED};
+use crate::{
+    graphql::{
+        context::{get_conn_from_context, get_renderer_from_context},
+        loader::DataLoader as AppLoader,
+    },
+    markdown::{derive_excerpt, reading_time_minutes, word_count},
+    posts::{
+        PostSearchRepository, PostTranslationsRepository, PostsFilter as PostsRepoFilter,
+        PostsOrder as PostsRepoOrder, PostsRepository, RelatedPostsRepository,
+    },
+    series::SeriesRepository,
+    tags::{
+        graphql::{PostTagUuid, Tag},
+        TagsRepository,
+    },
+    uploads::graphql::file_url,
+    user::{
+        graphql::{User, UserLink, UserUuid},
+        ProfilesRepository,
+    },
+    utils::pagination::{Cursor, Page},
+};
+use async_graphql::{
+    connection::{self, query, Connection, Edge, EmptyFields},
+    dataloader::DataLoader,
+    ComplexObject, Context, Error as GraphqlError, Object, Result as GraphqlResult, SimpleObject,
+    ID,
+};
+use chrono::{DateTime, Utc};
+use entity::{enums::Status, posts};
+use sea_orm::DatabaseConnection;
+use snafu::prelude::*;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A published post as the public site sees it. It leaves out the Markdown source and anything
+/// that identifies the author beyond their uuid.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct PublishedPost {
+    pub uuid: ID,
+    pub slug: String,
+    pub title: String,
+    pub html: String,
+    pub locale: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[graphql(skip)]
+    raw: String,
+    #[graphql(skip)]
+    custom_excerpt: Option<String>,
+    #[graphql(skip)]
+    created_by: Uuid,
+}
+
+/// A published post found by a search, with the matched terms wrapped in `<mark>` tags. There's
+/// no snippet, it would be cut out of the Markdown: the excerpt of the post stands in for it.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct PublishedPostSearchResult {
+    pub post: PublishedPost,
+    pub title: String,
+    pub rank: f64,
+}
+
+/// The series of a published post, only its published parts are counted.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct PublishedPostSeries {
+    pub slug: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// Starts at 1.
+    pub position: i32,
+    pub total: i32,
+    pub previous: Option<PublishedPost>,
+    pub next: Option<PublishedPost>,
+}
+
+/// What the public site knows about someone credited on a post, never their email.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct PublicAuthor {
+    pub uuid: ID,
+    pub display_name: Option<String>,
+    pub handle: Option<String>,
+    #[graphql(skip)]
+    bio: Option<String>,
+    #[graphql(skip)]
+    avatar_uuid: Option<Uuid>,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct PublicCredit {
+    pub author: PublicAuthor,
+    pub role: CreditRole,
+}
+
+#[ComplexObject]
+impl PublishedPost {
+    async fn author(&self, ctx: &Context<'_>) -> GraphqlResult<PublicAuthor> {
+        let loader = ctx.data_unchecked::<DataLoader<AppLoader>>();
+        let author: User = loader
+            .load_one(UserUuid::new(self.created_by))
+            .await?
+            .context(InvalidAuthorSnafu)?;
+
+        Ok(author.into())
+    }
+
+    /// Who wrote or worked on the post, in credit order.
+    async fn authors(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<PublicCredit>> {
+        let loader = ctx.data_unchecked::<DataLoader<AppLoader>>();
+        let uuid = Uuid::from_str(&self.uuid)?;
+        let credits = loader
+            .load_one(PostAuthorUuid::new(uuid))
+            .await?
+            .unwrap_or_default();
+
+        let mut users = loader
+            .load_many(credits.iter().map(|c| UserUuid::new(c.user_uuid)))
+            .await?;
+
+        credits
+            .into_iter()
+            .map(|credit| -> GraphqlResult<PublicCredit> {
+                let user = users
+                    .remove(&UserUuid::new(credit.user_uuid))
+                    .context(InvalidAuthorSnafu)?;
+
+                Ok(PublicCredit {
+                    author: user.into(),
+                    role: credit.role,
+                })
+            })
+            .collect()
+    }
+
+    async fn tags(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<Tag>> {
+        let loader = ctx.data_unchecked::<DataLoader<AppLoader>>();
+        let uuid = Uuid::from_str(&self.uuid)?;
+        let tags = loader.load_one(PostTagUuid::new(uuid)).await?;
+
+        Ok(tags.unwrap_or_default())
+    }
+
+    async fn excerpt(&self) -> String {
+        match &self.custom_excerpt {
+            Some(excerpt) => excerpt.clone(),
+            None => derive_excerpt(&self.raw),
+        }
+    }
+
+    async fn word_count(&self) -> i32 {
+        word_count(&self.raw) as i32
+    }
+
+    async fn reading_time_minutes(&self) -> i32 {
+        reading_time_minutes(word_count(&self.raw)) as i32
+    }
+
+    /// What to read next, ranked by shared tags and content.
+    async fn related_posts(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u64>,
+    ) -> GraphqlResult<Vec<PublishedPost>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+        let limit = limit.unwrap_or(DEFAULT_RELATED);
+
+        // Anonymous reads write too, a post without cached rankings is ranked and stored here.
+        let posts = RelatedPostsRepository::find_for(conn, uuid, limit)
+            .await?
+            .into_iter()
+            .filter_map(PublishedPost::try_from_published)
+            .collect();
+
+        Ok(posts)
+    }
+
+    /// The published versions of the post in other languages.
+    async fn translations(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<PublishedPost>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        let posts = PostTranslationsRepository::find_for(conn, uuid, Some(Status::Published))
+            .await?
+            .into_iter()
+            .filter_map(PublishedPost::try_from_published)
+            .collect();
+
+        Ok(posts)
+    }
+
+    async fn series(&self, ctx: &Context<'_>) -> GraphqlResult<Option<PublishedPostSeries>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        let (series, parts) = match SeriesRepository::find_by_post(conn, uuid).await? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        let parts: Vec<PublishedPost> = parts
+            .into_iter()
+            .filter_map(PublishedPost::try_from_published)
+            .collect();
+        let index = match parts.iter().position(|p| p.uuid == self.uuid) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+
+        Ok(Some(PublishedPostSeries {
+            slug: series.slug,
+            title: series.title,
+            description: series.description,
+            position: index as i32 + 1,
+            total: parts.len() as i32,
+            previous: index.checked_sub(1).and_then(|i| parts.get(i)).cloned(),
+            next: parts.get(index + 1).cloned(),
+        }))
+    }
+}
+
+#[ComplexObject]
+impl PublicAuthor {
+    /// The bio rendered to HTML.
+    async fn bio(&self, ctx: &Context<'_>) -> GraphqlResult<Option<String>> {
+        let renderer = get_renderer_from_context(ctx).await?;
+
+        Ok(self.bio.as_deref().map(|bio| renderer.render(bio)))
+    }
+
+    async fn avatar_url(&self) -> Option<String> {
+        self.avatar_uuid.as_ref().map(file_url)
+    }
+
+    async fn links(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<UserLink>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        let links = ProfilesRepository::find_links(conn, uuid)
+            .await?
+            .into_iter()
+            .map(|link| link.into())
+            .collect();
+
+        Ok(links)
+    }
+
+    /// Published posts the author is credited on.
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+        order_by: Option<PostsOrder>,
+    ) -> GraphqlResult<Connection<Cursor, PublishedPost, EmptyFields, EmptyFields>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let filter = PostsRepoFilter {
+            status: Some(Status::Published),
+            credited: Some(Uuid::from_str(&self.uuid)?),
+            ..Default::default()
+        };
+
+        published_connection(conn, filter, order_by, after, before, first, last).await
+    }
+}
+
+impl From<User> for PublicAuthor {
+    fn from(user: User) -> Self {
+        PublicAuthor {
+            uuid: user.uuid,
+            display_name: user.display_name,
+            handle: user.handle,
+            bio: user.bio,
+            avatar_uuid: user.avatar_uuid,
+        }
+    }
+}
+
+/// Queries that don't need a token, they can only ever see published posts.
+#[derive(Default)]
+pub struct PublicPostsQuery;
+
+#[Object]
+impl PublicPostsQuery {
+    pub async fn published_post(
+        &self,
+        ctx: &Context<'_>,
+        slug: String,
+    ) -> GraphqlResult<Option<PublishedPost>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let post = PostsRepository::find_by_slug(conn, &slug).await?;
+
+        match post {
+            Some(p) => Ok(PublishedPost::try_from_published(p)),
+            None => Ok(None),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn published_posts(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+        tag: Option<String>,
+        include_subtags: Option<bool>,
+        locale: Option<String>,
+        order_by: Option<PostsOrder>,
+    ) -> GraphqlResult<Connection<Cursor, PublishedPost, EmptyFields, EmptyFields>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let filter = PostsRepoFilter {
+            status: Some(Status::Published),
+            tag,
+            include_subtags: include_subtags.unwrap_or_default(),
+            locale: parse_locale(locale)?,
+            ..Default::default()
+        };
+
+        published_connection(conn, filter, order_by, after, before, first, last).await
+    }
+
+    /// The public profile behind an author page, authors without a handle don't have one.
+    pub async fn author(
+        &self,
+        ctx: &Context<'_>,
+        handle: String,
+    ) -> GraphqlResult<Option<PublicAuthor>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let author = ProfilesRepository::find_by_handle(conn, &handle).await?;
+
+        Ok(author.map(|user| User::from(user).into()))
+    }
+
+    /// The page of a tag, tags without published posts don't have one.
+    pub async fn published_tag(
+        &self,
+        ctx: &Context<'_>,
+        slug: String,
+    ) -> GraphqlResult<Option<Tag>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let tag = match TagsRepository::find_by_slug(conn, &slug).await? {
+            Some(tag) => tag,
+            None => return Ok(None),
+        };
+
+        let tag = TagsRepository::with_usage(conn, vec![tag])
+            .await?
+            .into_iter()
+            .find(|(_, usage)| usage.published_posts > 0)
+            .map(|(tag, _)| Tag::from(tag));
+
+        Ok(tag)
+    }
+
+    /// Full-text search over the title, content and tags of the published posts.
+    pub async fn search_published_posts(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        first: Option<i32>,
+        after: Option<String>,
+        locale: Option<String>,
+    ) -> GraphqlResult<Connection<Cursor, PublishedPostSearchResult, EmptyFields, EmptyFields>>
+    {
+        let conn = get_conn_from_context(ctx).await?;
+        let locale = parse_locale(locale)?;
+
+        // The `query` argument shadows the connection helper.
+        connection::query(
+            after,
+            None,
+            first,
+            None,
+            |after, _before, first, _last| async move {
+                let page = Page {
+                    after,
+                    first,
+                    ..Default::default()
+                };
+
+                let status = Some(Status::Published);
+                let hits =
+                    PostSearchRepository::search(conn, &query, status, locale, &page).await?;
+
+                let mut connection = Connection::new(hits.has_previous_page, hits.has_next_page);
+                for hit in hits.items {
+                    let cursor = hit.cursor();
+                    if let Some(post) = PublishedPost::try_from_published(hit.post) {
+                        let result = PublishedPostSearchResult {
+                            post,
+                            title: hit.title,
+                            rank: hit.rank,
+                        };
+                        connection.edges.push(Edge::new(cursor, result));
+                    }
+                }
+
+                Ok::<_, GraphqlError>(connection)
+            },
+        )
+        .await
+    }
+}
+
+/// A page of published posts, shared by the listings of the public site.
+pub async fn published_connection(
+    conn: &DatabaseConnection,
+    filter: PostsRepoFilter,
+    order_by: Option<PostsOrder>,
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+) -> GraphqlResult<Connection<Cursor, PublishedPost, EmptyFields, EmptyFields>> {
+    let order: PostsRepoOrder = order_by.unwrap_or_default().into();
+
+    query(
+        after,
+        before,
+        first,
+        last,
+        |after, before, first, last| async move {
+            ensure!(first.is_none() || last.is_none(), AmbiguousPageSnafu);
+
+            let page = Page {
+                after,
+                before,
+                first,
+                last,
+            };
+
+            let posts = PostsRepository::find_page(conn, filter, order, &page).await?;
+
+            let mut connection = Connection::new(posts.has_previous_page, posts.has_next_page);
+            for post in posts.items {
+                let cursor = order.cursor(&post);
+                if let Some(post) = PublishedPost::try_from_published(post) {
+                    connection.edges.push(Edge::new(cursor, post));
+                }
+            }
+
+            Ok::<_, GraphqlError>(connection)
+        },
+    )
+    .await
+}
+
+impl PublishedPost {
+    /// Only published posts make it through, anything else is treated as if it didn't exist.
+    fn try_from_published(post: posts::Model) -> Option<Self> {
+        if post.status != Status::Published.to_string() {
+            return None;
+        }
+
+        let uuid = Uuid::from_bytes(post.uuid());
+        let created_by = Uuid::from_bytes(post.created_by());
+
+        Some(PublishedPost {
+            uuid: uuid.into(),
+            slug: post.slug,
+            title: post.title,
+            html: post.html,
+            locale: post.locale,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+            raw: post.raw,
+            custom_excerpt: post.excerpt,
+            created_by,
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Post has Invalid author"))]
+    InvalidAuthor,
+
+    #[snafu(display("Can't paginate with both first and last"))]
+    AmbiguousPage,
+}
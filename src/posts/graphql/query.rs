@@ -1,14 +1,52 @@
-use super::Post;
+use super::{
+    parse_locale, DiffLine, HighlightLanguage, Post, PostSearchResult, Redirect, Revision, Status,
+};
 use crate::{
     authorization::graphql::{Action, Resource, RoleGuard},
-    graphql::context::get_conn_from_context,
-    posts::PostsRepository,
+    graphql::context::{get_conn_from_context, get_renderer_from_context},
+    posts::{
+        PostRedirectsRepository, PostRevisionsRepository, PostSearchRepository,
+        PostsFilter as PostsRepoFilter, PostsOrder as PostsRepoOrder, PostsRepository,
+    },
+    utils::{
+        diff::diff_lines,
+        pagination::{Cursor, Page},
+    },
+};
+use async_graphql::{
+    connection::{self, query, Connection, Edge, EmptyFields},
+    Context, Enum, Error as GraphqlError, InputObject, Object, Result as GraphqlResult, ID,
 };
-use async_graphql::{Context, Error as GraphqlError, Object, Result as GraphqlResult};
+use chrono::{DateTime, Utc};
 use snafu::prelude::*;
 use std::str::FromStr;
 use uuid::Uuid;
 
+#[derive(InputObject, Default)]
+pub struct PostsFilter {
+    pub status: Option<Status>,
+    pub tag: Option<String>,
+    /// Also matches the posts carrying a tag below `tag`, at any depth.
+    pub include_subtags: Option<bool>,
+    pub locale: Option<String>,
+    pub author: Option<ID>,
+    /// Posts the user is credited on, whatever their role.
+    pub credited: Option<ID>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Enum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PostsOrder {
+    CreatedAtAsc,
+    #[default]
+    CreatedAtDesc,
+    UpdatedAtAsc,
+    UpdatedAtDesc,
+}
+
 #[derive(Default)]
 pub struct PostsQuery;
 
@@ -28,18 +66,243 @@ impl PostsQuery {
     }
 
     #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Read)")]
-    pub async fn all_posts(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<Post>> {
+    pub async fn post_by_slug(
+        &self,
+        ctx: &Context<'_>,
+        slug: String,
+    ) -> GraphqlResult<Option<Post>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let post = PostsRepository::find_by_slug(conn, &slug).await?;
+
+        match post {
+            Some(p) => Ok(Some(p.try_into()?)),
+            None => Ok(None),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Read)")]
+    pub async fn all_posts(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+        filter: Option<PostsFilter>,
+        order_by: Option<PostsOrder>,
+    ) -> GraphqlResult<Connection<Cursor, Post, EmptyFields, EmptyFields>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let filter = filter.unwrap_or_default().try_into()?;
+        let order: PostsRepoOrder = order_by.unwrap_or_default().into();
+
+        query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                ensure!(first.is_none() || last.is_none(), AmbiguousPageSnafu);
+
+                let page = Page {
+                    after,
+                    before,
+                    first,
+                    last,
+                };
+
+                let posts = PostsRepository::find_page(conn, filter, order, &page).await?;
+
+                let mut connection = Connection::new(posts.has_previous_page, posts.has_next_page);
+                for post in posts.items {
+                    let cursor = order.cursor(&post);
+                    let post: Post = post.try_into()?;
+                    connection.edges.push(Edge::new(cursor, post));
+                }
+
+                Ok::<_, GraphqlError>(connection)
+            },
+        )
+        .await
+    }
+
+    /// Full-text search over the title, content and tags of every post, best matches first.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Read)")]
+    pub async fn search_posts(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        first: Option<i32>,
+        after: Option<String>,
+        status: Option<Status>,
+        locale: Option<String>,
+    ) -> GraphqlResult<Connection<Cursor, PostSearchResult, EmptyFields, EmptyFields>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let status = status.map(|s| s.into());
+        let locale = parse_locale(locale)?;
+
+        // The `query` argument shadows the connection helper.
+        connection::query(
+            after,
+            None,
+            first,
+            None,
+            |after, _before, first, _last| async move {
+                let page = Page {
+                    after,
+                    first,
+                    ..Default::default()
+                };
+
+                let hits =
+                    PostSearchRepository::search(conn, &query, status, locale, &page).await?;
+
+                let mut connection = Connection::new(hits.has_previous_page, hits.has_next_page);
+                for hit in hits.items {
+                    let cursor = hit.cursor();
+                    let result: PostSearchResult = hit.try_into()?;
+                    connection.edges.push(Edge::new(cursor, result));
+                }
+
+                Ok::<_, GraphqlError>(connection)
+            },
+        )
+        .await
+    }
+
+    /// Finds the post that used to live under the given slug.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Read)")]
+    pub async fn redirect(&self, ctx: &Context<'_>, slug: String) -> GraphqlResult<Option<Post>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let post = PostRedirectsRepository::resolve(conn, &slug).await?;
+
+        match post {
+            Some(p) => Ok(Some(p.try_into()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Every slug that changed, so the static site can turn them into permanent redirects.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Read)")]
+    pub async fn redirects(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<Redirect>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let redirects = PostRedirectsRepository::find_all(conn)
+            .await?
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+
+        Ok(redirects)
+    }
+
+    /// The history of a post, newest revision first.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Read)")]
+    pub async fn revisions(
+        &self,
+        ctx: &Context<'_>,
+        post_uuid: String,
+    ) -> GraphqlResult<Vec<Revision>> {
         let conn = get_conn_from_context(ctx).await?;
+        let post_uuid = Uuid::from_str(&post_uuid)?;
 
-        let posts: Result<Vec<Post>, _> = PostsRepository::find_all(conn)
+        let revisions = PostRevisionsRepository::find_by_post(conn, post_uuid)
             .await?
             .into_iter()
-            .map(|p| p.try_into())
+            .map(|r| r.into())
             .collect();
 
-        match posts {
-            Ok(p) => Ok(p),
-            Err(err) => Err(GraphqlError::from(err)),
+        Ok(revisions)
+    }
+
+    /// Line based diff of the Markdown between two revisions of the same post.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Read)")]
+    pub async fn revision_diff(
+        &self,
+        ctx: &Context<'_>,
+        from: String,
+        to: String,
+    ) -> GraphqlResult<Vec<DiffLine>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let from = Uuid::from_str(&from)?;
+        let to = Uuid::from_str(&to)?;
+
+        let from = PostRevisionsRepository::find_by_id(conn, from)
+            .await?
+            .context(MissingRevisionSnafu { uuid: from })?;
+        let to = PostRevisionsRepository::find_by_id(conn, to)
+            .await?
+            .context(MissingRevisionSnafu { uuid: to })?;
+
+        ensure!(from.post_uuid == to.post_uuid, UnrelatedRevisionsSnafu);
+
+        let lines = diff_lines(&from.raw, &to.raw)
+            .into_iter()
+            .map(|(change, line)| DiffLine {
+                change: change.into(),
+                line: line.to_string(),
+            })
+            .collect();
+
+        Ok(lines)
+    }
+
+    /// Languages fenced code blocks get highlighted in, anything else is kept as plain code.
+    #[graphql(guard = "RoleGuard::new(Resource::Post, Action::Read)")]
+    pub async fn highlight_languages(
+        &self,
+        ctx: &Context<'_>,
+    ) -> GraphqlResult<Vec<HighlightLanguage>> {
+        let renderer = get_renderer_from_context(ctx).await?;
+        let languages = renderer
+            .languages()
+            .into_iter()
+            .map(HighlightLanguage::from)
+            .collect();
+
+        Ok(languages)
+    }
+}
+
+impl TryFrom<PostsFilter> for PostsRepoFilter {
+    type Error = GraphqlError;
+
+    fn try_from(filter: PostsFilter) -> Result<Self, Self::Error> {
+        let author = match filter.author {
+            Some(id) => Some(Uuid::from_str(&id)?),
+            None => None,
+        };
+
+        let credited = match filter.credited {
+            Some(id) => Some(Uuid::from_str(&id)?),
+            None => None,
+        };
+
+        Ok(PostsRepoFilter {
+            status: filter.status.map(|s| s.into()),
+            tag: filter.tag,
+            tagged: None,
+            include_subtags: filter.include_subtags.unwrap_or_default(),
+            locale: parse_locale(filter.locale)?,
+            author,
+            credited,
+            created_after: filter.created_after,
+            created_before: filter.created_before,
+            updated_after: filter.updated_after,
+            updated_before: filter.updated_before,
+        })
+    }
+}
+
+impl From<PostsOrder> for PostsRepoOrder {
+    fn from(order: PostsOrder) -> Self {
+        match order {
+            PostsOrder::CreatedAtAsc => PostsRepoOrder::CreatedAtAsc,
+            PostsOrder::CreatedAtDesc => PostsRepoOrder::CreatedAtDesc,
+            PostsOrder::UpdatedAtAsc => PostsRepoOrder::UpdatedAtAsc,
+            PostsOrder::UpdatedAtDesc => PostsRepoOrder::UpdatedAtDesc,
         }
     }
 }
@@ -47,4 +310,15 @@ impl PostsQuery {
 #[derive(Debug, Snafu)]
 pub enum Error {
     InvalidPost,
+
+    #[snafu(display("Can't paginate with both first and last"))]
+    AmbiguousPage,
+
+    #[snafu(display("Revision does not exist with uuid {}", uuid))]
+    MissingRevision {
+        uuid: Uuid,
+    },
+
+    #[snafu(display("Revisions belong to different posts"))]
+    UnrelatedRevisions,
 }
@@ -1,4 +1,17 @@
+mod authors;
 pub mod graphql;
+pub mod publisher;
+mod redirects;
+mod related;
 mod repository;
+mod revisions;
+mod search;
+mod translations;
 
+pub use authors::{Credit, Error as AuthorsError, PostAuthorsRepository};
+pub use redirects::{Error as RedirectsError, PostRedirectsRepository};
+pub use related::{Error as RelatedError, RelatedPostsRepository, MAX_RELATED};
 pub use repository::*;
+pub use revisions::{Error as RevisionsError, PostRevisionsRepository};
+pub use search::{Error as SearchError, PostSearchRepository, SearchHit};
+pub use translations::{Error as TranslationsError, PostTranslationsRepository};
@@ -0,0 +1,50 @@
+use crate::{
+    builds::coordinator::notify_change, db::Db, posts::PostsRepository, utils::datetime::get_now,
+};
+use rocket::{
+    fairing::AdHoc,
+    tokio::{self, time},
+};
+use sea_orm_rocket::Database;
+use std::{env, time::Duration};
+
+/// Fairing that starts the background task publishing scheduled posts once they are due.
+pub fn init_publisher() -> AdHoc {
+    AdHoc::on_liftoff("Scheduled Publisher", |rocket| {
+        Box::pin(async move {
+            let conn = match Db::fetch(rocket) {
+                Some(db) => db.conn.clone(),
+                None => {
+                    error!("Database is not available, scheduled posts won't be published");
+                    return;
+                }
+            };
+
+            let interval = env::var("PUBLISHER_INTERVAL_SECS")
+                .ok()
+                .and_then(|secs| secs.parse::<u64>().ok())
+                .unwrap_or(60);
+
+            tokio::spawn(async move {
+                let mut interval = time::interval(Duration::from_secs(interval));
+
+                loop {
+                    interval.tick().await;
+
+                    match PostsRepository::publish_due(&conn, get_now()).await {
+                        Ok(published) => {
+                            if !published.is_empty() {
+                                notify_change(&conn, "Scheduled posts published").await;
+                            }
+
+                            for post in published {
+                                info!("Published scheduled post: {}", post.slug);
+                            }
+                        }
+                        Err(err) => error!("Failed to publish scheduled posts: {}", err),
+                    }
+                }
+            });
+        })
+    })
+}
@@ -0,0 +1,88 @@
+use entity::{
+    post_redirects::{self, Entity as PostRedirect},
+    posts::{self, Entity as Post},
+};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+pub struct PostRedirectsRepository;
+
+impl PostRedirectsRepository {
+    /// Keeps the previous slug of a post around, so old links can be redirected to it.
+    pub async fn record<C>(conn: &C, post_uuid: Uuid, slug: String) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        // A slug can only point to a single post, the latest one to drop it takes over.
+        PostRedirectsRepository::release(conn, &slug).await?;
+
+        let redirect = post_redirects::ActiveModel {
+            post_uuid: Set(post_uuid.as_bytes().to_vec()),
+            slug: Set(slug),
+            ..Default::default()
+        };
+
+        PostRedirect::insert(redirect)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Removes the redirect for a slug that is being used by a post again.
+    pub async fn release<C>(conn: &C, slug: &str) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        PostRedirect::delete_many()
+            .filter(post_redirects::Column::Slug.eq(slug))
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Maps an old slug to the post that used to have it.
+    pub async fn resolve(conn: &DatabaseConnection, slug: &str) -> Result<Option<posts::Model>> {
+        let redirect = PostRedirect::find()
+            .filter(post_redirects::Column::Slug.eq(slug))
+            .find_also_related(Post)
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(redirect.and_then(|(_, post)| post))
+    }
+
+    pub async fn find_all(
+        conn: &DatabaseConnection,
+    ) -> Result<Vec<(post_redirects::Model, posts::Model)>> {
+        let redirects = PostRedirect::find()
+            .find_also_related(Post)
+            .order_by_asc(post_redirects::Column::CreatedAt)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let redirects = redirects
+            .into_iter()
+            .filter_map(|(redirect, post)| post.map(|p| (redirect, p)))
+            .collect();
+
+        Ok(redirects)
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Post Redirects Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+}
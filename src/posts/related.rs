@@ -0,0 +1,454 @@
+use crate::{
+    posts::{PostSearchRepository, SearchError},
+    utils::{datetime::get_now, uuid::get_uuid_bytes},
+};
+use entity::{
+    enums::Status,
+    post_tags::{self, Entity as PostTag},
+    posts::{self, Entity as Post},
+    related_posts::{self, Entity as RelatedPost},
+    TagsForPost,
+};
+use pulldown_cmark::{Event, Parser};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, FromQueryResult, JoinType, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait, RelationTrait, Statement, Value,
+};
+use snafu::prelude::*;
+use std::{cmp::Ordering, collections::HashMap};
+use uuid::Uuid;
+
+/// Related posts kept per post, requests for more than this get this many.
+pub const MAX_RELATED: u64 = 20;
+
+/// Posts sharing terms with the current one that are considered.
+const SIMILAR_CANDIDATES: u64 = 50;
+
+/// Most frequent terms of a post used to look for similar ones.
+const KEY_TERMS: usize = 12;
+
+/// A shared tag weighs twice as much as being the closest match by content.
+const TAG_WEIGHT: f64 = 2.0;
+
+/// Words too common to say anything about what a post is about.
+const STOP_WORDS: &[&str] = &[
+    "about", "also", "been", "because", "could", "from", "have", "into", "just", "like", "more",
+    "only", "should", "some", "than", "that", "their", "them", "then", "there", "they", "this",
+    "what", "when", "which", "will", "with", "would", "your",
+];
+
+#[derive(Debug, FromQueryResult)]
+struct SharedTagsRow {
+    post_uuid: Vec<u8>,
+    shared: i64,
+}
+
+pub struct RelatedPostsRepository;
+
+impl RelatedPostsRepository {
+    /// Published posts related to the given one and in its locale, most related first. They are
+    /// ranked on first use and kept in `related_posts` until the tags of a post change or a post
+    /// of the locale is published or unpublished. A ranked post is also related to itself, so
+    /// one nothing relates to isn't ranked on every read. Ranking writes to the database, even
+    /// when it's the public API reading.
+    pub async fn find_for(
+        conn: &DatabaseConnection,
+        uuid: Uuid,
+        limit: u64,
+    ) -> Result<Vec<posts::Model>> {
+        let cached = RelatedPost::find()
+            .filter(related_posts::Column::PostUuid.eq(uuid.as_bytes().to_vec()))
+            .count(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        if cached == 0 {
+            RelatedPostsRepository::rank(conn, uuid).await?;
+        }
+
+        // Already ranked within the locale, this only catches posts whose locale changed since.
+        let locale = Post::find()
+            .select_only()
+            .column(posts::Column::Locale)
+            .filter(posts::Column::Uuid.eq(uuid.as_bytes().to_vec()))
+            .into_query();
+
+        Post::find()
+            .join(
+                JoinType::InnerJoin,
+                related_posts::Relation::Posts2.def().rev(),
+            )
+            .filter(related_posts::Column::PostUuid.eq(uuid.as_bytes().to_vec()))
+            .filter(related_posts::Column::RelatedUuid.ne(uuid.as_bytes().to_vec()))
+            .filter(posts::Column::Status.eq(Status::Published.to_string()))
+            .filter(posts::Column::Locale.in_subquery(locale))
+            .order_by_desc(related_posts::Column::Score)
+            .order_by_asc(posts::Column::Uuid)
+            .limit(limit.min(MAX_RELATED))
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Drops the cached posts of every post that shares a tag with the given one, or lists it
+    /// as related, since their ranking may change with it.
+    pub async fn invalidate<C>(conn: &C, post: &posts::Model) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let tags: Vec<Vec<u8>> = post
+            .find_linked(TagsForPost)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .into_iter()
+            .map(|tag| tag.uuid)
+            .collect();
+
+        let sharing_tags = PostTag::find()
+            .select_only()
+            .column(post_tags::Column::PostUuid)
+            .filter(post_tags::Column::TagUuid.is_in(tags))
+            .into_query();
+
+        RelatedPost::delete_many()
+            .filter(
+                Condition::any()
+                    .add(related_posts::Column::PostUuid.eq(post.uuid.clone()))
+                    .add(related_posts::Column::RelatedUuid.eq(post.uuid.clone()))
+                    .add(related_posts::Column::PostUuid.in_subquery(sharing_tags)),
+            )
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Drops the cached posts of every post in the locale, for when a post is published or
+    /// unpublished: any of them may relate to it by content alone, without sharing a tag.
+    pub async fn invalidate_locale<C>(conn: &C, locale: &str) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let in_locale = Post::find()
+            .select_only()
+            .column(posts::Column::Uuid)
+            .filter(posts::Column::Locale.eq(locale))
+            .into_query();
+
+        RelatedPost::delete_many()
+            .filter(related_posts::Column::PostUuid.in_subquery(in_locale))
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Drops the cached posts of every post carrying one of the tags, for when the tags
+    /// themselves are merged or deleted.
+    pub async fn invalidate_tagged<C>(conn: &C, tags: Vec<Vec<u8>>) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let tagged = PostTag::find()
+            .select_only()
+            .column(post_tags::Column::PostUuid)
+            .filter(post_tags::Column::TagUuid.is_in(tags))
+            .into_query();
+
+        RelatedPost::delete_many()
+            .filter(related_posts::Column::PostUuid.in_subquery(tagged))
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Scores other published posts in the same locale by the tags they share with the post and
+    /// how close their content is, then stores the best ones. Translations share their tags, the
+    /// other locales are left out before keeping the best or they would take every slot.
+    async fn rank(conn: &DatabaseConnection, uuid: Uuid) -> Result<()> {
+        let post = Post::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .context(PostNotFoundSnafu { uuid })?;
+
+        let tags: Vec<Vec<u8>> = post
+            .find_linked(TagsForPost)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .into_iter()
+            .map(|tag| tag.uuid)
+            .collect();
+
+        let mut scores: HashMap<Uuid, f64> = HashMap::new();
+
+        let shared_tags = PostTag::find()
+            .select_only()
+            .column(post_tags::Column::PostUuid)
+            .column_as(
+                Expr::tbl(post_tags::Entity, post_tags::Column::Uuid).count(),
+                "shared",
+            )
+            .join(JoinType::InnerJoin, post_tags::Relation::Posts.def())
+            .filter(post_tags::Column::TagUuid.is_in(tags))
+            .filter(post_tags::Column::PostUuid.ne(post.uuid.clone()))
+            .filter(posts::Column::Status.eq(Status::Published.to_string()))
+            .filter(posts::Column::Locale.eq(post.locale.clone()))
+            .group_by(post_tags::Column::PostUuid)
+            .into_model::<SharedTagsRow>()
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        for row in shared_tags {
+            let uuid = Uuid::from_bytes(get_uuid_bytes(&row.post_uuid));
+            *scores.entry(uuid).or_default() += row.shared as f64 * TAG_WEIGHT;
+        }
+
+        let terms = key_terms(&post.title, &post.raw);
+        let similar =
+            PostSearchRepository::similar(conn, &terms, &post.locale, uuid, SIMILAR_CANDIDATES)
+                .await
+                .context(SearchFailedSnafu)?;
+
+        // bm25 ranks are negative and lower is better, the closest match ends up with 1.
+        if let Some((_, best)) = similar.first().copied().filter(|(_, rank)| *rank < 0.0) {
+            for (uuid, rank) in similar {
+                *scores.entry(uuid).or_default() += rank / best;
+            }
+        }
+
+        let mut ranked: Vec<(Uuid, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|(a_uuid, a), (b_uuid, b)| {
+            b.partial_cmp(a)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a_uuid.cmp(b_uuid))
+        });
+        ranked.truncate(MAX_RELATED as usize);
+
+        // The row relating the post to itself marks it as ranked, even when nothing else is.
+        let rows: Vec<(Uuid, f64)> = std::iter::once((uuid, 0.0)).chain(ranked).collect();
+        let now = get_now();
+        let placeholders = vec!["(?, ?, ?, ?, ?)"; rows.len()].join(", ");
+        let values: Vec<Value> = rows
+            .into_iter()
+            .flat_map(|(related_uuid, score)| {
+                vec![
+                    Uuid::new_v4().as_bytes().to_vec().into(),
+                    post.uuid.clone().into(),
+                    related_uuid.as_bytes().to_vec().into(),
+                    score.into(),
+                    now.into(),
+                ]
+            })
+            .collect();
+
+        // Concurrent first reads rank the post at the same time, whatever the first one stored
+        // is kept instead of listing every post twice.
+        let sql = format!(
+            r#"
+            INSERT OR IGNORE INTO `related_posts` (uuid, post_uuid, related_uuid, score, created_at)
+            VALUES {};
+            "#,
+            placeholders
+        );
+
+        conn.execute(Statement::from_sql_and_values(
+            conn.get_database_backend(),
+            &sql,
+            values,
+        ))
+        .await
+        .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+}
+
+/// Most frequent meaningful words of the post, words in the title count three times.
+fn key_terms(title: &str, raw: &str) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    let text = Parser::new(raw).filter_map(|event| match event {
+        Event::Text(t) => Some((t.to_string(), 1)),
+        _ => None,
+    });
+
+    for (text, weight) in std::iter::once((title.to_string(), 3)).chain(text) {
+        for word in text.split(|c: char| !c.is_alphanumeric()) {
+            let word = word.to_lowercase();
+            if word.chars().count() < 4 || STOP_WORDS.contains(&word.as_str()) {
+                continue;
+            }
+
+            *counts.entry(word).or_default() += weight;
+        }
+    }
+
+    let mut terms: Vec<(String, usize)> = counts.into_iter().collect();
+    terms.sort_by(|(a_word, a), (b_word, b)| b.cmp(a).then_with(|| a_word.cmp(b_word)));
+
+    terms
+        .into_iter()
+        .take(KEY_TERMS)
+        .map(|(word, _)| word)
+        .collect()
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Related Posts Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Post with uuid {} not found", uuid))]
+    PostNotFound { uuid: Uuid },
+
+    #[snafu(display("Failed in PostSearchRepository: {}", source))]
+    SearchFailed { source: SearchError },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::testing,
+        posts::{ChangePostStatusInput, NewPostInput, PostsRepository},
+    };
+
+    async fn publish(
+        conn: &DatabaseConnection,
+        user: Uuid,
+        locale: &str,
+        title: &str,
+        raw: &str,
+        tags: &[&str],
+    ) -> Uuid {
+        let input = NewPostInput {
+            raw: raw.to_string(),
+            html: raw.to_string(),
+            locale: locale.to_string(),
+            ..testing::new_post(user, title, tags)
+        };
+
+        testing::publish(conn, input).await
+    }
+
+    #[rocket::async_test]
+    async fn ranks_a_post_once_even_when_nothing_is_related() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        let lonely = publish(&conn, user, "en", "Lonely", "Rustaceans", &[]).await;
+
+        for _ in 0..2 {
+            let related = RelatedPostsRepository::find_for(&conn, lonely, 5)
+                .await
+                .unwrap();
+            assert!(related.is_empty());
+
+            let cached = RelatedPost::find()
+                .filter(related_posts::Column::PostUuid.eq(lonely.as_bytes().to_vec()))
+                .count(&conn)
+                .await
+                .unwrap();
+            assert_eq!(cached, 1);
+        }
+    }
+
+    #[rocket::async_test]
+    async fn publishing_a_post_reranks_its_locale() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        let lonely = publish(
+            &conn,
+            user,
+            "en",
+            "Lonely",
+            "Rustaceans writing compilers",
+            &[],
+        )
+        .await;
+
+        let related = RelatedPostsRepository::find_for(&conn, lonely, 5)
+            .await
+            .unwrap();
+        assert!(related.is_empty());
+
+        // Shares no tag, only its content relates it to the ranked post.
+        let compilers = publish(
+            &conn,
+            user,
+            "en",
+            "Compilers",
+            "Compilers for rustaceans",
+            &[],
+        )
+        .await;
+
+        let related = RelatedPostsRepository::find_for(&conn, lonely, 5)
+            .await
+            .unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].uuid, compilers.as_bytes().to_vec());
+
+        let input = ChangePostStatusInput {
+            uuid: compilers,
+            status: Status::Draft,
+            publish_at: None,
+        };
+        PostsRepository::change_post_status(&conn, input)
+            .await
+            .unwrap();
+
+        let related = RelatedPostsRepository::find_for(&conn, lonely, 5)
+            .await
+            .unwrap();
+        assert!(related.is_empty());
+    }
+
+    #[rocket::async_test]
+    async fn ranking_twice_lists_each_post_once() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        let first = publish(&conn, user, "en", "First", "Ownership", &["rust"]).await;
+        let second = publish(&conn, user, "en", "Second", "Borrowing", &["rust"]).await;
+
+        // What two concurrent first reads do, both find nothing cached and rank the post.
+        RelatedPostsRepository::rank(&conn, first).await.unwrap();
+        RelatedPostsRepository::rank(&conn, first).await.unwrap();
+
+        let related = RelatedPostsRepository::find_for(&conn, first, 5)
+            .await
+            .unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].uuid, second.as_bytes().to_vec());
+    }
+
+    #[rocket::async_test]
+    async fn translations_dont_take_the_slots_of_the_locale() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        let post = publish(&conn, user, "en", "Traits", "Traits", &["rust", "types"]).await;
+        let english = publish(&conn, user, "en", "Generics", "Generics", &["rust"]).await;
+
+        // Each one shares more tags than the English post does.
+        for i in 0..MAX_RELATED {
+            let title = format!("Rasgos {}", i);
+            publish(&conn, user, "es", &title, "Rasgos", &["rust", "types"]).await;
+        }
+
+        let related = RelatedPostsRepository::find_for(&conn, post, 5)
+            .await
+            .unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].uuid, english.as_bytes().to_vec());
+    }
+}
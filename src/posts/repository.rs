@@ -1,67 +1,190 @@
 use crate::{
-    tags::{Error as TagsError, TagsRepository},
-    utils::uuid::get_uuid_bytes,
+    posts::{
+        AuthorsError, PostAuthorsRepository, PostRedirectsRepository, PostRevisionsRepository,
+        PostTranslationsRepository, RedirectsError, RelatedError, RelatedPostsRepository,
+        RevisionsError, TranslationsError,
+    },
+    tags::{name_key, Error as TagsError, SynonymsError, TagSynonymsRepository, TagsRepository},
+    utils::{
+        datetime::get_now,
+        locale::normalize_locale,
+        pagination::{Cursor, Page, Paginated},
+        slug::{slugify, with_suffix},
+        uuid::get_uuid_bytes,
+    },
+    webhooks::{Error as WebhooksError, WebhooksRepository},
 };
+use chrono::{DateTime, ParseError as DateParseError, Utc};
 use entity::{
-    enums::Status,
+    enums::{Status, WebhookEvent},
+    post_authors::{self, Entity as PostAuthor},
+    post_redirects::{self, Entity as PostRedirect},
     post_tags::{
         ActiveModel as PostTagsActiveModel, Column as PostTagsColumn, Entity as PostTagsEntity,
     },
     posts::{self, Entity as Post},
+    tags::{self, Entity as TagEntity},
+    PostsForTag,
 };
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+    sea_query::{Expr, SimpleExpr},
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, ModelTrait, Order, QueryFilter, QueryOrder, QuerySelect, QueryTrait, Select, Set,
+    TransactionTrait, Value,
 };
 use snafu::prelude::*;
 use uuid::Uuid;
 
 pub struct NewPostInput {
     pub title: String,
+    pub slug: Option<String>,
     pub raw: String,
     pub html: String,
+    pub excerpt: Option<String>,
     pub tags: Vec<String>,
+    pub locale: String,
+    /// The post this one is a translation of.
+    pub translation_of: Option<Uuid>,
     pub created_by: Uuid,
 }
 
 pub struct UpdatePostInput {
     pub uuid: Uuid,
     pub title: String,
+    pub slug: Option<String>,
     pub raw: String,
     pub html: String,
+    /// `None` keeps the current excerpt, a blank one goes back to the one derived from the content.
+    pub excerpt: Option<String>,
     pub tags: Vec<String>,
+    /// `None` keeps the current locale.
+    pub locale: Option<String>,
+    pub updated_by: Uuid,
 }
 
 pub struct ChangePostStatusInput {
     pub uuid: Uuid,
     pub status: Status,
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PostsFilter {
+    pub status: Option<Status>,
+    /// Matched whatever its case or spacing.
+    pub tag: Option<String>,
+    /// Same as `tag`, for when the tag is already known by its uuid.
+    pub tagged: Option<Uuid>,
+    /// Also matches the posts carrying a tag below `tag` or `tagged`, at any depth.
+    pub include_subtags: bool,
+    pub locale: Option<String>,
+    pub author: Option<Uuid>,
+    /// Posts the user is credited on, whatever their role.
+    pub credited: Option<Uuid>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum PostsOrder {
+    CreatedAtAsc,
+    #[default]
+    CreatedAtDesc,
+    UpdatedAtAsc,
+    UpdatedAtDesc,
+}
+
+impl PostsOrder {
+    fn column(&self) -> posts::Column {
+        match self {
+            PostsOrder::CreatedAtAsc | PostsOrder::CreatedAtDesc => posts::Column::CreatedAt,
+            PostsOrder::UpdatedAtAsc | PostsOrder::UpdatedAtDesc => posts::Column::UpdatedAt,
+        }
+    }
+
+    fn is_ascending(&self) -> bool {
+        matches!(self, PostsOrder::CreatedAtAsc | PostsOrder::UpdatedAtAsc)
+    }
+
+    /// Cursor pointing to the given post within this order.
+    pub fn cursor(&self, post: &posts::Model) -> Cursor {
+        let key = match self {
+            PostsOrder::CreatedAtAsc | PostsOrder::CreatedAtDesc => post.created_at,
+            PostsOrder::UpdatedAtAsc | PostsOrder::UpdatedAtDesc => post.updated_at,
+        };
+
+        Cursor::new(key.to_rfc3339(), Uuid::from_bytes(post.uuid()))
+    }
 }
 
 pub struct PostsRepository;
 
 impl PostsRepository {
     pub async fn create(conn: &DatabaseConnection, input: NewPostInput) -> Result<posts::Model> {
+        let locale = normalize_locale(&input.locale).context(InvalidLocaleSnafu {
+            locale: input.locale.clone(),
+        })?;
+
+        // Everything below is rolled back when a step fails, the post is saved along with its
+        // tags, credits and first revision or not at all.
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        // Only a slug chosen by hand takes over a redirect, a derived one never collides with one.
+        let explicit_slug = input.slug.is_some();
+        let slug = PostsRepository::resolve_slug(&txn, &input.title, input.slug, None).await?;
+        if explicit_slug {
+            PostRedirectsRepository::release(&txn, &slug)
+                .await
+                .context(RedirectsRepoFailedSnafu)?;
+        }
+
         let post = posts::ActiveModel {
             title: Set(input.title),
+            slug: Set(slug),
             raw: Set(input.raw),
             html: Set(input.html),
+            excerpt: Set(clean_excerpt(input.excerpt)),
+            locale: Set(locale),
             created_by: Set(input.created_by.as_bytes().to_vec()),
             ..Default::default()
         };
 
         let result = Post::insert(post)
-            .exec(conn)
+            .exec(&txn)
             .await
             .context(QueryFailedSnafu)?;
 
         let last_insert_id = Uuid::from_bytes(get_uuid_bytes(&result.last_insert_id));
 
-        let post = PostsRepository::find_by_id(conn, last_insert_id)
+        let post = PostsRepository::find_by_id(&txn, last_insert_id)
             .await?
             .context(PostNotFoundSnafu {
                 uuid: last_insert_id,
             })?;
 
-        PostsRepository::register_tags(conn, &post, input.tags).await?;
+        PostAuthorsRepository::record_creator(&txn, &post)
+            .await
+            .context(AuthorsRepoFailedSnafu)?;
+        let post = match input.translation_of {
+            Some(original) => PostTranslationsRepository::link(&txn, last_insert_id, original)
+                .await
+                .context(TranslationsRepoFailedSnafu)?
+                .into_iter()
+                .find(|p| p.uuid == post.uuid)
+                .unwrap_or(post),
+            None => post,
+        };
+        PostsRepository::register_tags(&txn, &post, input.tags).await?;
+        PostRevisionsRepository::record(&txn, &post, input.created_by)
+            .await
+            .context(RevisionsRepoFailedSnafu)?;
+        WebhooksRepository::emit(&txn, WebhookEvent::PostCreated, &post)
+            .await
+            .context(WebhooksRepoFailedSnafu)?;
+
+        txn.commit().await.context(QueryFailedSnafu)?;
 
         Ok(post)
     }
@@ -70,18 +193,68 @@ impl PostsRepository {
         conn: &DatabaseConnection,
         input: UpdatePostInput,
     ) -> Result<posts::Model> {
-        let post = PostsRepository::find_by_id(conn, input.uuid)
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let post = PostsRepository::find_by_id(&txn, input.uuid)
             .await?
             .context(PostNotFoundSnafu { uuid: input.uuid })?;
 
+        // The slug only follows the title when it changes, so existing links keep working when
+        // only the content gets edited.
+        let slug = match (input.slug, post.title == input.title) {
+            (None, true) => post.slug.clone(),
+            (slug, _) => {
+                PostsRepository::resolve_slug(&txn, &input.title, slug, Some(input.uuid)).await?
+            }
+        };
+
+        let locale = match input.locale {
+            Some(raw) => {
+                let locale = normalize_locale(&raw).context(InvalidLocaleSnafu { locale: raw })?;
+                let taken = PostTranslationsRepository::is_locale_taken(&txn, &post, &locale)
+                    .await
+                    .context(TranslationsRepoFailedSnafu)?;
+                ensure!(!taken, LocaleAlreadyTranslatedSnafu { locale });
+
+                locale
+            }
+            None => post.locale.clone(),
+        };
+
+        if slug != post.slug {
+            PostRedirectsRepository::release(&txn, &slug)
+                .await
+                .context(RedirectsRepoFailedSnafu)?;
+            PostRedirectsRepository::record(&txn, input.uuid, post.slug.clone())
+                .await
+                .context(RedirectsRepoFailedSnafu)?;
+        }
+
+        let excerpt = match input.excerpt {
+            Some(excerpt) => clean_excerpt(Some(excerpt)),
+            None => post.excerpt.clone(),
+        };
+
         let mut post: posts::ActiveModel = post.into();
         post.title = Set(input.title);
+        post.slug = Set(slug);
         post.raw = Set(input.raw);
         post.html = Set(input.html);
+        post.excerpt = Set(excerpt);
+        post.locale = Set(locale);
+        post.updated_at = Set(get_now());
+
+        let post: posts::Model = post.update(&txn).await.context(QueryFailedSnafu)?;
 
-        let post: posts::Model = post.update(conn).await.context(QueryFailedSnafu)?;
+        PostsRepository::register_tags(&txn, &post, input.tags).await?;
+        PostRevisionsRepository::record(&txn, &post, input.updated_by)
+            .await
+            .context(RevisionsRepoFailedSnafu)?;
+        WebhooksRepository::emit(&txn, WebhookEvent::PostUpdated, &post)
+            .await
+            .context(WebhooksRepoFailedSnafu)?;
 
-        PostsRepository::register_tags(conn, &post, input.tags).await?;
+        txn.commit().await.context(QueryFailedSnafu)?;
 
         Ok(post)
     }
@@ -90,43 +263,426 @@ impl PostsRepository {
         conn: &DatabaseConnection,
         input: ChangePostStatusInput,
     ) -> Result<posts::Model> {
-        let post = PostsRepository::find_by_id(conn, input.uuid)
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let post = PostsRepository::find_by_id(&txn, input.uuid)
             .await?
             .context(PostNotFoundSnafu { uuid: input.uuid })?;
 
+        let now = get_now();
+        let published_at = post.published_at;
+
         let mut post: posts::ActiveModel = post.into();
+        match input.status {
+            Status::Scheduled => {
+                let publish_at = input.publish_at.context(MissingPublishDateSnafu)?;
+                post.publish_at = Set(Some(publish_at));
+            }
+            Status::Published => {
+                post.publish_at = Set(None);
+                post.published_at = Set(published_at.or(Some(now)));
+            }
+            _ => {
+                post.publish_at = Set(None);
+            }
+        };
         post.status = Set(input.status.to_string());
+        post.updated_at = Set(now);
+
+        let post: posts::Model = post.update(&txn).await.context(QueryFailedSnafu)?;
+
+        // Only published posts are recommended, so it comes in or out of the related posts of
+        // its locale, even the ones only related to it by their content.
+        RelatedPostsRepository::invalidate_locale(&txn, &post.locale)
+            .await
+            .context(RelatedRepoFailedSnafu)?;
+
+        WebhooksRepository::emit(&txn, WebhookEvent::PostStatusChanged, &post)
+            .await
+            .context(WebhooksRepoFailedSnafu)?;
+
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(post)
+    }
+
+    /// Publishes every scheduled post whose time has come, returning the ones that went live.
+    pub async fn publish_due(
+        conn: &DatabaseConnection,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<posts::Model>> {
+        let due = Post::find()
+            .filter(posts::Column::Status.eq(Status::Scheduled.to_string()))
+            .filter(posts::Column::PublishAt.lte(now))
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?;
 
-        let post: posts::Model = post.update(conn).await.context(QueryFailedSnafu)?;
+        // Each post goes live on its own, one failing is logged and doesn't hold back the others.
+        let mut published = Vec::with_capacity(due.len());
+        for post in due {
+            let slug = post.slug.clone();
+
+            match PostsRepository::publish_scheduled(conn, post, now).await {
+                Ok(post) => published.push(post),
+                Err(err) => error!("Failed to publish scheduled post {}: {}", slug, err),
+            }
+        }
+
+        Ok(published)
+    }
+
+    async fn publish_scheduled(
+        conn: &DatabaseConnection,
+        post: posts::Model,
+        now: DateTime<Utc>,
+    ) -> Result<posts::Model> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let mut post: posts::ActiveModel = post.into();
+        post.status = Set(Status::Published.to_string());
+        post.publish_at = Set(None);
+        post.published_at = Set(Some(now));
+        post.updated_at = Set(now);
+
+        let post: posts::Model = post.update(&txn).await.context(QueryFailedSnafu)?;
+        RelatedPostsRepository::invalidate_locale(&txn, &post.locale)
+            .await
+            .context(RelatedRepoFailedSnafu)?;
+        WebhooksRepository::emit(&txn, WebhookEvent::PostStatusChanged, &post)
+            .await
+            .context(WebhooksRepoFailedSnafu)?;
+
+        txn.commit().await.context(QueryFailedSnafu)?;
 
         Ok(post)
     }
 
+    /// Runs the stored HTML of every post through `rewrite` and saves the ones that changed,
+    /// returning how many were saved.
+    pub async fn rewrite_html<F>(conn: &DatabaseConnection, rewrite: F) -> Result<usize>
+    where
+        F: Fn(&str) -> String,
+    {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+        let posts = Post::find().all(&txn).await.context(QueryFailedSnafu)?;
+
+        let mut rewritten = 0;
+        for post in posts {
+            let html = rewrite(&post.html);
+            if html == post.html {
+                continue;
+            }
+
+            let mut post: posts::ActiveModel = post.into();
+            post.html = Set(html);
+            post.update(&txn).await.context(QueryFailedSnafu)?;
+            rewritten += 1;
+        }
+
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(rewritten)
+    }
+
     pub async fn delete(conn: &DatabaseConnection, uuid: Uuid) -> Result<()> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let post = PostsRepository::find_by_id(&txn, uuid)
+            .await?
+            .context(PostNotFoundSnafu { uuid })?;
+
+        PostTranslationsRepository::unlink(&txn, uuid)
+            .await
+            .context(TranslationsRepoFailedSnafu)?;
+
         Post::delete_by_id(uuid.as_bytes().to_vec())
-            .exec(conn)
+            .exec(&txn)
             .await
             .context(QueryFailedSnafu)?;
 
+        WebhooksRepository::emit(&txn, WebhookEvent::PostDeleted, &post)
+            .await
+            .context(WebhooksRepoFailedSnafu)?;
+
+        txn.commit().await.context(QueryFailedSnafu)?;
+
         Ok(())
     }
 
-    pub async fn find_by_id(conn: &DatabaseConnection, uuid: Uuid) -> Result<Option<posts::Model>> {
+    pub async fn find_by_id<C>(conn: &C, uuid: Uuid) -> Result<Option<posts::Model>>
+    where
+        C: ConnectionTrait,
+    {
         Post::find_by_id(uuid.as_bytes().to_vec())
             .one(conn)
             .await
             .context(QueryFailedSnafu)
     }
 
-    pub async fn find_all(conn: &DatabaseConnection) -> Result<Vec<posts::Model>> {
-        Post::find().all(conn).await.context(QueryFailedSnafu)
+    pub async fn find_by_slug(
+        conn: &DatabaseConnection,
+        slug: &str,
+    ) -> Result<Option<posts::Model>> {
+        Post::find()
+            .filter(posts::Column::Slug.eq(slug))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Keyset pagination over the posts, the cursors in the page must come from the same order.
+    pub async fn find_page(
+        conn: &DatabaseConnection,
+        filter: PostsFilter,
+        order: PostsOrder,
+        page: &Page,
+    ) -> Result<Paginated<posts::Model>> {
+        let filter = PostsRepository::resolve_tag(conn, filter).await?;
+        let mut query = PostsRepository::filtered(filter);
+
+        if let Some(after) = &page.after {
+            query = query.filter(PostsRepository::cursor_condition(order, after, true)?);
+        }
+
+        if let Some(before) = &page.before {
+            query = query.filter(PostsRepository::cursor_condition(order, before, false)?);
+        }
+
+        // Going backwards reads the rows in reverse, `Paginated` puts them back in order.
+        let ord = match order.is_ascending() != page.is_backward() {
+            true => Order::Asc,
+            false => Order::Desc,
+        };
+
+        let rows = query
+            .order_by(order.column(), ord.clone())
+            .order_by(posts::Column::Uuid, ord)
+            .limit(page.limit() as u64 + 1)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(Paginated::from_rows(rows, page))
+    }
+
+    /// Most recently published posts, optionally only the ones with the given tag.
+    pub async fn find_latest_published(
+        conn: &DatabaseConnection,
+        tagged: Option<Uuid>,
+        limit: u64,
+    ) -> Result<Vec<posts::Model>> {
+        let filter = PostsFilter {
+            status: Some(Status::Published),
+            tagged,
+            ..Default::default()
+        };
+
+        PostsRepository::filtered(filter)
+            .order_by(posts::Column::PublishedAt, Order::Desc)
+            .order_by(posts::Column::Uuid, Order::Desc)
+            .limit(limit)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
     }
 
-    async fn register_tags(
+    /// A `tag` given by one of its synonyms is swapped for the key of the tag it stands for.
+    async fn resolve_tag(
         conn: &DatabaseConnection,
-        post: &posts::Model,
-        tags: Vec<String>,
-    ) -> Result<()> {
+        mut filter: PostsFilter,
+    ) -> Result<PostsFilter> {
+        if let Some(tag) = &filter.tag {
+            let key = name_key(tag);
+            let resolved = TagSynonymsRepository::resolve(conn, vec![key.clone()])
+                .await
+                .context(SynonymsRepoFailedSnafu)?;
+
+            let key = match resolved.into_iter().next() {
+                Some((_, tag)) => tag.normalized_name,
+                None => key,
+            };
+            filter.tag = Some(key);
+        }
+
+        Ok(filter)
+    }
+
+    fn filtered(filter: PostsFilter) -> Select<Post> {
+        let mut query = match filter.tagged {
+            Some(tag) if !filter.include_subtags => {
+                tags::Model::default(tag).find_linked(PostsForTag)
+            }
+            Some(tag) => Post::find().filter(tagged_below("uuid", tag.as_bytes().to_vec())),
+            None => Post::find(),
+        };
+
+        if let Some(status) = filter.status {
+            query = query.filter(posts::Column::Status.eq(status.to_string()));
+        }
+
+        if let Some(locale) = filter.locale {
+            query = query.filter(posts::Column::Locale.eq(locale));
+        }
+
+        if let Some(author) = filter.author {
+            query = query.filter(posts::Column::CreatedBy.eq(author.as_bytes().to_vec()));
+        }
+
+        if let Some(user) = filter.credited {
+            let credited = PostAuthor::find()
+                .select_only()
+                .column(post_authors::Column::PostUuid)
+                .filter(post_authors::Column::UserUuid.eq(user.as_bytes().to_vec()))
+                .into_query();
+
+            query = query.filter(posts::Column::Uuid.in_subquery(credited));
+        }
+
+        if let Some(tag) = filter.tag {
+            query = match filter.include_subtags {
+                true => query.filter(tagged_below("normalized_name", name_key(&tag))),
+                false => {
+                    let tagged = PostTagsEntity::find()
+                        .select_only()
+                        .column(PostTagsColumn::PostUuid)
+                        .inner_join(TagEntity)
+                        .filter(tags::Column::NormalizedName.eq(name_key(&tag)))
+                        .into_query();
+
+                    query.filter(posts::Column::Uuid.in_subquery(tagged))
+                }
+            };
+        }
+
+        if let Some(date) = filter.created_after {
+            query = query.filter(posts::Column::CreatedAt.gte(date));
+        }
+
+        if let Some(date) = filter.created_before {
+            query = query.filter(posts::Column::CreatedAt.lte(date));
+        }
+
+        if let Some(date) = filter.updated_after {
+            query = query.filter(posts::Column::UpdatedAt.gte(date));
+        }
+
+        if let Some(date) = filter.updated_before {
+            query = query.filter(posts::Column::UpdatedAt.lte(date));
+        }
+
+        query
+    }
+
+    /// Rows placed after (or before) the cursor, using the uuid to break ties.
+    fn cursor_condition(order: PostsOrder, cursor: &Cursor, after: bool) -> Result<Condition> {
+        let key: DateTime<Utc> = DateTime::parse_from_rfc3339(&cursor.key)
+            .context(InvalidCursorSnafu)?
+            .with_timezone(&Utc);
+        let uuid = cursor.uuid.as_bytes().to_vec();
+        let column = order.column();
+
+        let condition = if order.is_ascending() == after {
+            Condition::any().add(column.gt(key)).add(
+                Condition::all()
+                    .add(column.eq(key))
+                    .add(posts::Column::Uuid.gt(uuid)),
+            )
+        } else {
+            Condition::any().add(column.lt(key)).add(
+                Condition::all()
+                    .add(column.eq(key))
+                    .add(posts::Column::Uuid.lt(uuid)),
+            )
+        };
+
+        Ok(condition)
+    }
+
+    /// Finds the slug a post should use. An explicit slug must be free, while a slug derived
+    /// from the title gets a numeric suffix until it no longer collides with another post, or
+    /// with an old slug still redirecting to another post.
+    async fn resolve_slug<C>(
+        conn: &C,
+        title: &str,
+        slug: Option<String>,
+        current: Option<Uuid>,
+    ) -> Result<String>
+    where
+        C: ConnectionTrait,
+    {
+        let mut query = Post::find();
+        if let Some(uuid) = current {
+            query = query.filter(posts::Column::Uuid.ne(uuid.as_bytes().to_vec()));
+        }
+
+        if let Some(raw_slug) = slug {
+            let slug = slugify(&raw_slug);
+            ensure!(!slug.is_empty(), InvalidSlugSnafu { slug: raw_slug });
+
+            let existing = query
+                .filter(posts::Column::Slug.eq(slug.clone()))
+                .one(conn)
+                .await
+                .context(QueryFailedSnafu)?;
+
+            return match existing {
+                Some(_) => Err(Error::SlugAlreadyExists { slug }),
+                None => Ok(slug),
+            };
+        }
+
+        let base = match slugify(title) {
+            s if s.is_empty() => String::from("post"),
+            s => s,
+        };
+
+        let mut taken: Vec<String> = query
+            .filter(
+                Condition::any()
+                    .add(posts::Column::Slug.eq(base.clone()))
+                    .add(posts::Column::Slug.like(&format!("{}-%", base))),
+            )
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .into_iter()
+            .map(|p| p.slug)
+            .collect();
+
+        // The post can get one of its own old slugs back, the others keep their redirects.
+        let mut redirects = PostRedirect::find().filter(
+            Condition::any()
+                .add(post_redirects::Column::Slug.eq(base.clone()))
+                .add(post_redirects::Column::Slug.like(&format!("{}-%", base))),
+        );
+        if let Some(uuid) = current {
+            redirects =
+                redirects.filter(post_redirects::Column::PostUuid.ne(uuid.as_bytes().to_vec()));
+        }
+
+        let redirected = redirects.all(conn).await.context(QueryFailedSnafu)?;
+        taken.extend(redirected.into_iter().map(|r| r.slug));
+
+        let mut suffix = 1;
+        let mut slug = base.clone();
+        while taken.contains(&slug) {
+            suffix += 1;
+            slug = with_suffix(&base, suffix);
+        }
+
+        Ok(slug)
+    }
+
+    async fn register_tags<C>(conn: &C, post: &posts::Model, tags: Vec<String>) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        // Posts sharing the tags the post is about to lose rank it differently now.
+        RelatedPostsRepository::invalidate(conn, post)
+            .await
+            .context(RelatedRepoFailedSnafu)?;
+
         // 1. Unattach the tags that are linked to the post.
         PostTagsEntity::delete_many()
             .filter(PostTagsColumn::PostUuid.eq(post.uuid.clone()))
@@ -139,7 +695,7 @@ impl PostsRepository {
             .await
             .context(TagsRepoFailedSnafu)?;
 
-        // 3. Attach the tags to the Post
+        // 3. Attach the tags to the Post, a post may have none at all.
         let tags_to_attach: Vec<PostTagsActiveModel> = tags_to_attach
             .into_iter()
             .map(|tag| PostTagsActiveModel {
@@ -149,15 +705,48 @@ impl PostsRepository {
             })
             .collect();
 
-        PostTagsEntity::insert_many(tags_to_attach)
-            .exec(conn)
+        if !tags_to_attach.is_empty() {
+            PostTagsEntity::insert_many(tags_to_attach)
+                .exec(conn)
+                .await
+                .context(TagsQueryFailedSnafu)?;
+        }
+
+        // Same for the posts sharing the tags it just got.
+        RelatedPostsRepository::invalidate(conn, post)
             .await
-            .context(TagsQueryFailedSnafu)?;
+            .context(RelatedRepoFailedSnafu)?;
 
         Ok(())
     }
 }
 
+/// Posts carrying the tag whose `column` matches the value, or any tag below it. `UNION` drops
+/// the tags already seen, so even a hierarchy edited into a loop terminates.
+fn tagged_below(column: &str, value: impl Into<Value>) -> SimpleExpr {
+    let sql = format!(
+        r#"`posts`.`uuid` IN (
+            WITH RECURSIVE subtags(uuid) AS (
+                SELECT uuid FROM tags WHERE {} = ?
+                UNION
+                SELECT tags.uuid FROM tags INNER JOIN subtags ON tags.parent_uuid = subtags.uuid
+            )
+            SELECT post_tags.post_uuid FROM post_tags
+            INNER JOIN subtags ON subtags.uuid = post_tags.tag_uuid
+        )"#,
+        column
+    );
+
+    Expr::cust_with_values(&sql, vec![value.into()])
+}
+
+/// A blank excerpt is the same as not having one.
+fn clean_excerpt(excerpt: Option<String>) -> Option<String> {
+    excerpt
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(Snafu, Debug)]
@@ -171,6 +760,235 @@ pub enum Error {
     #[snafu(display("Post with uuid {} not found", uuid))]
     PostNotFound { uuid: Uuid },
 
+    #[snafu(display("A scheduled post needs a date to be published at"))]
+    MissingPublishDate,
+
+    #[snafu(display("Cursor is not valid for the posts order: {}", source))]
+    InvalidCursor { source: DateParseError },
+
+    #[snafu(display("Slug '{}' is not valid", slug))]
+    InvalidSlug { slug: String },
+
+    #[snafu(display("Slug '{}' is already used by another post", slug))]
+    SlugAlreadyExists { slug: String },
+
+    #[snafu(display("Locale '{}' is not valid", locale))]
+    InvalidLocale { locale: String },
+
+    #[snafu(display("The post already has a translation in '{}'", locale))]
+    LocaleAlreadyTranslated { locale: String },
+
     #[snafu(display("Failed in PostsRepository: {}", source))]
     TagsRepoFailed { source: TagsError },
+
+    #[snafu(display("Failed in TagSynonymsRepository: {}", source))]
+    SynonymsRepoFailed { source: SynonymsError },
+
+    #[snafu(display("Failed in PostRedirectsRepository: {}", source))]
+    RedirectsRepoFailed { source: RedirectsError },
+
+    #[snafu(display("Failed in PostRevisionsRepository: {}", source))]
+    RevisionsRepoFailed { source: RevisionsError },
+
+    #[snafu(display("Failed in PostAuthorsRepository: {}", source))]
+    AuthorsRepoFailed { source: AuthorsError },
+
+    #[snafu(display("Failed in RelatedPostsRepository: {}", source))]
+    RelatedRepoFailed { source: RelatedError },
+
+    #[snafu(display("Failed in PostTranslationsRepository: {}", source))]
+    TranslationsRepoFailed { source: TranslationsError },
+
+    #[snafu(display("Failed in WebhooksRepository: {}", source))]
+    WebhooksRepoFailed { source: WebhooksError },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing;
+    use chrono::Duration;
+    use sea_orm::Statement;
+
+    #[rocket::async_test]
+    async fn filters_by_a_synonym_of_the_tag() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        let post = PostsRepository::create(&conn, testing::new_post(user, "Ownership", &["Rust"]))
+            .await
+            .unwrap();
+        PostsRepository::create(&conn, testing::new_post(user, "Borrowing", &["Go"]))
+            .await
+            .unwrap();
+
+        let rust = TagsRepository::find_or_create_tags(&conn, vec![String::from("rust")])
+            .await
+            .unwrap();
+        TagSynonymsRepository::add(&conn, Uuid::from_bytes(rust[0].uuid()), "rust-lang")
+            .await
+            .unwrap();
+
+        for include_subtags in [false, true] {
+            let filter = PostsFilter {
+                tag: Some(String::from(" Rust-Lang ")),
+                include_subtags,
+                ..Default::default()
+            };
+            let page =
+                PostsRepository::find_page(&conn, filter, PostsOrder::default(), &Page::default())
+                    .await
+                    .unwrap();
+
+            let titles: Vec<&str> = page.items.iter().map(|p| p.title.as_str()).collect();
+            assert_eq!(titles, vec![post.title.as_str()]);
+        }
+    }
+
+    #[rocket::async_test]
+    async fn publishes_the_due_posts_past_one_failing() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        let now = get_now();
+
+        let mut scheduled = Vec::new();
+        for title in ["Broken", "Working"] {
+            let post = PostsRepository::create(&conn, testing::new_post(user, title, &[]))
+                .await
+                .unwrap();
+            let input = ChangePostStatusInput {
+                uuid: Uuid::from_bytes(post.uuid()),
+                status: Status::Scheduled,
+                publish_at: Some(now - Duration::minutes(1)),
+            };
+            scheduled.push(
+                PostsRepository::change_post_status(&conn, input)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let sql = r#"
+        CREATE TRIGGER fail_publishing_broken BEFORE UPDATE ON posts WHEN NEW.title = 'Broken'
+        BEGIN
+            SELECT RAISE(ABORT, 'forced failure');
+        END;
+        "#;
+        conn.execute(Statement::from_string(
+            conn.get_database_backend(),
+            sql.to_owned(),
+        ))
+        .await
+        .unwrap();
+
+        let published = PostsRepository::publish_due(&conn, now).await.unwrap();
+        let titles: Vec<&str> = published.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, vec!["Working"]);
+
+        let broken = PostsRepository::find_by_id(&conn, Uuid::from_bytes(scheduled[0].uuid()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(broken.status, Status::Scheduled.to_string());
+    }
+
+    #[rocket::async_test]
+    async fn creating_a_post_rolls_back_when_its_tags_fail() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        testing::fail_inserts_into(&conn, "post_tags").await;
+
+        let created =
+            PostsRepository::create(&conn, testing::new_post(user, "Ownership", &["Rust"])).await;
+
+        assert!(created.is_err());
+        assert_eq!(testing::count_rows(&conn, "posts").await, 0);
+        assert_eq!(testing::count_rows(&conn, "post_authors").await, 0);
+        assert_eq!(testing::count_rows(&conn, "tags").await, 0);
+    }
+
+    #[rocket::async_test]
+    async fn creating_a_post_rolls_back_when_its_revision_fails() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        testing::fail_inserts_into(&conn, "post_revisions").await;
+
+        let created =
+            PostsRepository::create(&conn, testing::new_post(user, "Ownership", &["Rust"])).await;
+
+        assert!(created.is_err());
+        assert_eq!(testing::count_rows(&conn, "posts").await, 0);
+        assert_eq!(testing::count_rows(&conn, "post_tags").await, 0);
+        assert_eq!(testing::count_rows(&conn, "tags").await, 0);
+    }
+
+    #[rocket::async_test]
+    async fn updating_a_post_rolls_back_when_its_revision_fails() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        let post = PostsRepository::create(&conn, testing::new_post(user, "Ownership", &["Rust"]))
+            .await
+            .unwrap();
+        testing::fail_inserts_into(&conn, "post_revisions").await;
+
+        let uuid = Uuid::from_bytes(post.uuid());
+        let input = UpdatePostInput {
+            uuid,
+            title: String::from("Borrowing"),
+            slug: None,
+            raw: String::from("Borrowing"),
+            html: String::from("Borrowing"),
+            excerpt: None,
+            tags: vec![String::from("Go")],
+            locale: None,
+            updated_by: user,
+        };
+        let updated = PostsRepository::update_post(&conn, input).await;
+        assert!(updated.is_err());
+
+        let stored = PostsRepository::find_by_id(&conn, uuid)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.title, "Ownership");
+        assert_eq!(stored.slug, post.slug);
+        assert_eq!(testing::count_rows(&conn, "post_redirects").await, 0);
+
+        let tags: Vec<String> = TagEntity::find()
+            .inner_join(PostTagsEntity)
+            .filter(PostTagsColumn::PostUuid.eq(post.uuid.clone()))
+            .all(&conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        assert_eq!(tags, vec!["Rust"]);
+        assert_eq!(testing::count_rows(&conn, "tags").await, 1);
+    }
+
+    #[rocket::async_test]
+    async fn saving_a_post_without_tags_inserts_no_post_tags() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        // Any insert into `post_tags`, even of no rows at all, would fail now.
+        testing::fail_inserts_into(&conn, "post_tags").await;
+
+        let post = PostsRepository::create(&conn, testing::new_post(user, "Ownership", &[]))
+            .await
+            .unwrap();
+        let input = UpdatePostInput {
+            uuid: Uuid::from_bytes(post.uuid()),
+            title: String::from("Ownership"),
+            slug: None,
+            raw: String::from("Moved"),
+            html: String::from("Moved"),
+            excerpt: None,
+            tags: vec![],
+            locale: None,
+            updated_by: user,
+        };
+        PostsRepository::update_post(&conn, input).await.unwrap();
+
+        assert_eq!(testing::count_rows(&conn, "post_tags").await, 0);
+    }
 }
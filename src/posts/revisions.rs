@@ -0,0 +1,77 @@
+use crate::utils::uuid::get_uuid_bytes;
+use entity::{
+    post_revisions::{self, Entity as PostRevision},
+    posts,
+};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+pub struct PostRevisionsRepository;
+
+impl PostRevisionsRepository {
+    /// Stores a snapshot of the post as it is right now, written by the given user.
+    pub async fn record<C>(
+        conn: &C,
+        post: &posts::Model,
+        created_by: Uuid,
+    ) -> Result<post_revisions::Model>
+    where
+        C: ConnectionTrait,
+    {
+        let revision = post_revisions::ActiveModel {
+            post_uuid: Set(post.uuid.clone()),
+            title: Set(post.title.clone()),
+            raw: Set(post.raw.clone()),
+            created_by: Set(created_by.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let result = PostRevision::insert(revision)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let uuid = Uuid::from_bytes(get_uuid_bytes(&result.last_insert_id));
+        PostRevisionsRepository::find_by_id(conn, uuid)
+            .await?
+            .context(RevisionNotFoundSnafu { uuid })
+    }
+
+    pub async fn find_by_id<C>(conn: &C, uuid: Uuid) -> Result<Option<post_revisions::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        PostRevision::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// The history of a post, newest revision first.
+    pub async fn find_by_post(
+        conn: &DatabaseConnection,
+        post_uuid: Uuid,
+    ) -> Result<Vec<post_revisions::Model>> {
+        PostRevision::find()
+            .filter(post_revisions::Column::PostUuid.eq(post_uuid.as_bytes().to_vec()))
+            .order_by_desc(post_revisions::Column::CreatedAt)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Post Revisions Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Revision with uuid {} not found", uuid))]
+    RevisionNotFound { uuid: Uuid },
+}
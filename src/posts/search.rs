@@ -0,0 +1,225 @@
+use crate::utils::{
+    pagination::{Cursor, Page, Paginated},
+    uuid::get_uuid_bytes,
+    xml::escape,
+};
+use entity::{enums::Status, posts};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbErr, FromQueryResult, QueryResult, Statement, Value,
+};
+use snafu::prelude::*;
+use std::num::ParseFloatError;
+use uuid::Uuid;
+
+/// Markers put around matched terms by SQLite, they are swapped for `<mark>` once the rest of
+/// the text has been escaped so nothing from the post itself ends up as HTML.
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+/// Words around the match kept in the snippet of the content.
+const SNIPPET_TOKENS: u32 = 24;
+
+/// A post matching a search, ranked with bm25 where lower is better.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub post: posts::Model,
+    pub rank: f64,
+    /// Title with the matched terms wrapped in `<mark>`.
+    pub title: String,
+    /// Part of the Markdown around the best match, with the matched terms wrapped in `<mark>`.
+    /// Only for the admins, the Markdown holds what the sanitizer strips from the HTML.
+    pub snippet: String,
+}
+
+impl SearchHit {
+    pub fn cursor(&self) -> Cursor {
+        Cursor::new(self.rank, Uuid::from_bytes(self.post.uuid()))
+    }
+
+    fn from_row(row: &QueryResult) -> Result<Self> {
+        let post = posts::Model::from_query_result(row, "").context(QueryFailedSnafu)?;
+        let rank = row.try_get("", "search_rank").context(QueryFailedSnafu)?;
+        let title: String = row
+            .try_get("", "title_highlight")
+            .context(QueryFailedSnafu)?;
+        let snippet: String = row.try_get("", "snippet").context(QueryFailedSnafu)?;
+
+        Ok(SearchHit {
+            post,
+            rank,
+            title: mark(&title),
+            snippet: mark(&snippet),
+        })
+    }
+}
+
+pub struct PostSearchRepository;
+
+impl PostSearchRepository {
+    /// Full-text search over the title, Markdown and tag names of the posts, best matches first.
+    /// The index is kept up to date by triggers on the posts, post_tags and tags tables.
+    pub async fn search(
+        conn: &DatabaseConnection,
+        query: &str,
+        status: Option<Status>,
+        locale: Option<String>,
+        page: &Page,
+    ) -> Result<Paginated<SearchHit>> {
+        let expression = match match_expression(query) {
+            Some(expression) => expression,
+            None => return Ok(Paginated::from_rows(vec![], page)),
+        };
+
+        let mut conditions = vec![];
+        let mut values: Vec<Value> = vec![expression.into()];
+
+        if let Some(status) = status {
+            conditions.push("status = ?");
+            values.push(status.to_string().into());
+        }
+
+        if let Some(locale) = locale {
+            conditions.push("locale = ?");
+            values.push(locale.into());
+        }
+
+        if let Some(after) = &page.after {
+            let rank: f64 = after.key.parse().context(InvalidCursorSnafu)?;
+            let uuid = after.uuid.as_bytes().to_vec();
+
+            conditions.push("(search_rank > ? OR (search_rank = ? AND uuid > ?))");
+            values.push(rank.into());
+            values.push(rank.into());
+            values.push(uuid.into());
+        }
+
+        let filter = match conditions.is_empty() {
+            true => String::new(),
+            false => format!("WHERE {}", conditions.join(" AND ")),
+        };
+        values.push((page.limit() as i64 + 1).into());
+
+        // Matches in the title weigh the most, then tags, then the content.
+        let sql = format!(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    posts.*,
+                    bm25(posts_search, 0.0, 10.0, 1.0, 5.0) AS search_rank,
+                    highlight(posts_search, 1, '{start}', '{end}') AS title_highlight,
+                    snippet(posts_search, 2, '{start}', '{end}', '…', {tokens}) AS snippet
+                FROM posts_search
+                INNER JOIN posts ON posts.uuid = posts_search.uuid
+                WHERE posts_search MATCH ?
+            )
+            {filter}
+            ORDER BY search_rank, uuid
+            LIMIT ?
+            "#,
+            start = MATCH_START,
+            end = MATCH_END,
+            tokens = SNIPPET_TOKENS,
+            filter = filter,
+        );
+
+        let rows = conn
+            .query_all(Statement::from_sql_and_values(
+                conn.get_database_backend(),
+                &sql,
+                values,
+            ))
+            .await
+            .context(QueryFailedSnafu)?
+            .iter()
+            .map(SearchHit::from_row)
+            .collect::<Result<Vec<SearchHit>>>()?;
+
+        Ok(Paginated::from_rows(rows, page))
+    }
+
+    /// Published posts in the locale containing any of the terms, with their bm25 rank. Used to
+    /// find posts talking about the same things as another one.
+    pub async fn similar(
+        conn: &DatabaseConnection,
+        terms: &[String],
+        locale: &str,
+        exclude: Uuid,
+        limit: u64,
+    ) -> Result<Vec<(Uuid, f64)>> {
+        let expression = terms
+            .iter()
+            .map(|term| quote(term))
+            .collect::<Vec<String>>()
+            .join(" OR ");
+
+        if expression.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let sql = r#"
+            SELECT posts.uuid, bm25(posts_search, 0.0, 10.0, 1.0, 5.0) AS search_rank
+            FROM posts_search
+            INNER JOIN posts ON posts.uuid = posts_search.uuid
+            WHERE posts_search MATCH ? AND posts.status = ? AND posts.locale = ? AND posts.uuid != ?
+            ORDER BY search_rank
+            LIMIT ?
+            "#;
+        let values: Vec<Value> = vec![
+            expression.into(),
+            Status::Published.to_string().into(),
+            locale.into(),
+            exclude.as_bytes().to_vec().into(),
+            (limit as i64).into(),
+        ];
+
+        conn.query_all(Statement::from_sql_and_values(
+            conn.get_database_backend(),
+            sql,
+            values,
+        ))
+        .await
+        .context(QueryFailedSnafu)?
+        .iter()
+        .map(|row| {
+            let uuid: Vec<u8> = row.try_get("", "uuid")?;
+            let rank: f64 = row.try_get("", "search_rank")?;
+
+            Ok((Uuid::from_bytes(get_uuid_bytes(&uuid)), rank))
+        })
+        .collect::<Result<Vec<(Uuid, f64)>, DbErr>>()
+        .context(QueryFailedSnafu)
+    }
+}
+
+/// Turns what the user typed into an FTS5 query. Every word is quoted so operators and stray
+/// quotes are searched for literally, and the last one also matches as a prefix.
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query.split_whitespace().map(quote).collect();
+
+    match terms.is_empty() {
+        true => None,
+        false => Some(format!("{}*", terms.join(" "))),
+    }
+}
+
+/// A term FTS5 matches literally.
+fn quote(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', "\"\""))
+}
+
+fn mark(text: &str) -> String {
+    escape(text)
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Search Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Cursor is not valid for a search: {}", source))]
+    InvalidCursor { source: ParseFloatError },
+}
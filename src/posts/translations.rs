@@ -0,0 +1,229 @@
+use entity::{
+    enums::Status,
+    posts::{self, Entity as Post},
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+pub struct PostTranslationsRepository;
+
+impl PostTranslationsRepository {
+    /// Makes `post` a translation of `original`, joining whatever translations it already has.
+    /// A post leaves its previous group, and a group only has one post per locale.
+    pub async fn link<C>(
+        conn: &C,
+        post_uuid: Uuid,
+        original_uuid: Uuid,
+    ) -> Result<Vec<posts::Model>>
+    where
+        C: ConnectionTrait + TransactionTrait,
+    {
+        ensure!(post_uuid != original_uuid, SelfTranslationSnafu);
+
+        let post = PostTranslationsRepository::find_post(conn, post_uuid).await?;
+        let original = PostTranslationsRepository::find_post(conn, original_uuid).await?;
+
+        let group = original
+            .translation_group
+            .clone()
+            .unwrap_or_else(|| original.uuid.clone());
+
+        if post.translation_group.as_ref() == Some(&group) {
+            return PostTranslationsRepository::find_group(conn, &group).await;
+        }
+
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let taken = Post::find()
+            .filter(posts::Column::TranslationGroup.eq(group.clone()))
+            .filter(posts::Column::Locale.eq(post.locale.clone()))
+            .one(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+        ensure!(
+            taken.is_none() && original.locale != post.locale,
+            LocaleAlreadyTranslatedSnafu {
+                locale: post.locale.clone()
+            }
+        );
+
+        let previous = post.translation_group.clone();
+
+        if original.translation_group.is_none() {
+            let mut original: posts::ActiveModel = original.into();
+            original.translation_group = Set(Some(group.clone()));
+            original.update(&txn).await.context(QueryFailedSnafu)?;
+        }
+
+        let mut post: posts::ActiveModel = post.into();
+        post.translation_group = Set(Some(group.clone()));
+        post.update(&txn).await.context(QueryFailedSnafu)?;
+
+        if let Some(previous) = previous {
+            PostTranslationsRepository::dissolve_if_alone(&txn, &previous).await?;
+        }
+
+        let group = PostTranslationsRepository::find_group(&txn, &group).await?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(group)
+    }
+
+    /// Takes a post out of its translations, the others stay linked to each other.
+    pub async fn unlink<C>(conn: &C, post_uuid: Uuid) -> Result<posts::Model>
+    where
+        C: ConnectionTrait + TransactionTrait,
+    {
+        let post = PostTranslationsRepository::find_post(conn, post_uuid).await?;
+
+        let group = match post.translation_group.clone() {
+            Some(group) => group,
+            None => return Ok(post),
+        };
+
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let mut post: posts::ActiveModel = post.into();
+        post.translation_group = Set(None);
+        let post = post.update(&txn).await.context(QueryFailedSnafu)?;
+
+        PostTranslationsRepository::dissolve_if_alone(&txn, &group).await?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(post)
+    }
+
+    /// The other versions of a post, ordered by locale.
+    pub async fn find_for(
+        conn: &DatabaseConnection,
+        uuid: Uuid,
+        status: Option<Status>,
+    ) -> Result<Vec<posts::Model>> {
+        // A post without translations has a NULL group, which matches nothing.
+        let group = Post::find()
+            .select_only()
+            .column(posts::Column::TranslationGroup)
+            .filter(posts::Column::Uuid.eq(uuid.as_bytes().to_vec()))
+            .into_query();
+
+        let mut query = Post::find()
+            .filter(posts::Column::TranslationGroup.in_subquery(group))
+            .filter(posts::Column::Uuid.ne(uuid.as_bytes().to_vec()));
+
+        if let Some(status) = status {
+            query = query.filter(posts::Column::Status.eq(status.to_string()));
+        }
+
+        query
+            .order_by_asc(posts::Column::Locale)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Published posts of the given groups, used to list the alternates of many posts at once.
+    pub async fn find_published_in_groups(
+        conn: &DatabaseConnection,
+        groups: Vec<Vec<u8>>,
+    ) -> Result<Vec<posts::Model>> {
+        if groups.is_empty() {
+            return Ok(vec![]);
+        }
+
+        Post::find()
+            .filter(posts::Column::TranslationGroup.is_in(groups))
+            .filter(posts::Column::Status.eq(Status::Published.to_string()))
+            .order_by_asc(posts::Column::Locale)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Whether changing the locale of a post would give its group two posts in that locale.
+    pub async fn is_locale_taken<C>(conn: &C, post: &posts::Model, locale: &str) -> Result<bool>
+    where
+        C: ConnectionTrait,
+    {
+        let group = match &post.translation_group {
+            Some(group) => group.clone(),
+            None => return Ok(false),
+        };
+
+        let taken = Post::find()
+            .filter(posts::Column::TranslationGroup.eq(group))
+            .filter(posts::Column::Locale.eq(locale))
+            .filter(posts::Column::Uuid.ne(post.uuid.clone()))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(taken.is_some())
+    }
+
+    async fn find_group<C>(conn: &C, group: &[u8]) -> Result<Vec<posts::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        Post::find()
+            .filter(posts::Column::TranslationGroup.eq(group.to_vec()))
+            .order_by_asc(posts::Column::Locale)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// A post left alone in a group has nothing to link to anymore.
+    async fn dissolve_if_alone<C>(conn: &C, group: &[u8]) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let members = PostTranslationsRepository::find_group(conn, group).await?;
+        if members.len() > 1 {
+            return Ok(());
+        }
+
+        Post::update_many()
+            .col_expr(
+                posts::Column::TranslationGroup,
+                Expr::value(None::<Vec<u8>>),
+            )
+            .filter(posts::Column::TranslationGroup.eq(group.to_vec()))
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    async fn find_post<C>(conn: &C, uuid: Uuid) -> Result<posts::Model>
+    where
+        C: ConnectionTrait,
+    {
+        Post::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .context(PostNotFoundSnafu { uuid })
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Post Translations Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Post with uuid {} not found", uuid))]
+    PostNotFound { uuid: Uuid },
+
+    #[snafu(display("A post can't be a translation of itself"))]
+    SelfTranslation,
+
+    #[snafu(display("The post already has a translation in '{}'", locale))]
+    LocaleAlreadyTranslated { locale: String },
+}
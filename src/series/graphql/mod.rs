@@ -0,0 +1,94 @@
+mod mutation;
+mod query;
+
+pub use mutation::*;
+pub use query::*;
+
+use crate::{
+    graphql::context::get_conn_from_context, posts::graphql::Post, series::SeriesRepository,
+};
+use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
+use chrono::{DateTime, Utc};
+use entity::{posts, series};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Posts meant to be read one after the other, like the parts of a tutorial.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct Series {
+    pub uuid: ID,
+    pub slug: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[ComplexObject]
+impl Series {
+    /// Parts of the series in reading order.
+    async fn posts(&self, ctx: &Context<'_>) -> Result<Vec<Post>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        let posts = SeriesRepository::find_posts(conn, uuid)
+            .await?
+            .into_iter()
+            .map(Post::try_from)
+            .collect::<Result<Vec<Post>, _>>()?;
+
+        Ok(posts)
+    }
+}
+
+impl From<series::Model> for Series {
+    fn from(series: series::Model) -> Self {
+        let uuid = Uuid::from_bytes(series.uuid());
+
+        Series {
+            uuid: uuid.into(),
+            slug: series.slug,
+            title: series.title,
+            description: series.description,
+            created_at: series.created_at,
+            updated_at: series.updated_at,
+        }
+    }
+}
+
+/// Where a post stands in its series, with the parts around it.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct PostSeries {
+    pub series: Series,
+    /// Starts at 1.
+    pub position: i32,
+    pub total: i32,
+    pub previous: Option<Post>,
+    pub next: Option<Post>,
+}
+
+impl PostSeries {
+    /// Builds the navigation of `post` out of the parts of its series.
+    pub fn new(
+        series: series::Model,
+        parts: Vec<posts::Model>,
+        post: Uuid,
+    ) -> Result<Option<Self>> {
+        let index = match parts.iter().position(|p| p.uuid() == *post.as_bytes()) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+
+        let previous = index.checked_sub(1).and_then(|i| parts.get(i)).cloned();
+        let next = parts.get(index + 1).cloned();
+
+        Ok(Some(PostSeries {
+            series: series.into(),
+            position: index as i32 + 1,
+            total: parts.len() as i32,
+            previous: previous.map(Post::try_from).transpose()?,
+            next: next.map(Post::try_from).transpose()?,
+        }))
+    }
+}
@@ -0,0 +1,99 @@
+use super::Series;
+use crate::{
+    authorization::graphql::{Action, Resource, RoleGuard},
+    builds::coordinator::notify_change,
+    graphql::context::{get_conn_from_context, get_token_from_context},
+    series::{
+        NewSeriesInput as NewSeriesRepoInput, SeriesRepository,
+        UpdateSeriesInput as UpdateSeriesRepoInput,
+    },
+};
+use async_graphql::{Context, InputObject, Object, Result, ID};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(InputObject)]
+pub struct NewSeriesInput {
+    pub title: String,
+    pub slug: Option<String>,
+    pub description: Option<String>,
+    /// Uuids of the parts, in reading order.
+    pub posts: Vec<ID>,
+}
+
+#[derive(InputObject)]
+pub struct UpdateSeriesInput {
+    pub uuid: ID,
+    pub title: String,
+    pub slug: Option<String>,
+    pub description: Option<String>,
+    /// Uuids of the parts, in reading order. Posts left out are removed from the series.
+    pub posts: Vec<ID>,
+}
+
+#[derive(Default)]
+pub struct SeriesMutation;
+
+#[Object]
+impl SeriesMutation {
+    #[graphql(guard = "RoleGuard::new(Resource::Series, Action::Write)")]
+    pub async fn new_series(&self, ctx: &Context<'_>, input: NewSeriesInput) -> Result<Series> {
+        let conn = get_conn_from_context(ctx).await?;
+        let token = get_token_from_context(ctx).await?.expect("Missing Token");
+
+        let input = NewSeriesRepoInput {
+            title: input.title,
+            slug: input.slug,
+            description: input.description,
+            posts: parse_uuids(&input.posts)?,
+            created_by: token.uuid,
+        };
+
+        let series = SeriesRepository::create(conn, input).await?;
+        notify_change(conn, "Series created").await;
+
+        Ok(series.into())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Series, Action::Write)")]
+    pub async fn update_series(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateSeriesInput,
+    ) -> Result<Series> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let input = UpdateSeriesRepoInput {
+            uuid: Uuid::from_str(&input.uuid)?,
+            title: input.title,
+            slug: input.slug,
+            description: input.description,
+            posts: parse_uuids(&input.posts)?,
+        };
+
+        let series = SeriesRepository::update(conn, input).await?;
+        notify_change(conn, "Series updated").await;
+
+        Ok(series.into())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Series, Action::Write)")]
+    pub async fn delete_series(&self, ctx: &Context<'_>, uuid: String) -> Result<ID> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&uuid)?;
+
+        SeriesRepository::delete(conn, uuid).await?;
+        notify_change(conn, "Series deleted").await;
+
+        Ok(uuid.into())
+    }
+}
+
+fn parse_uuids(ids: &[ID]) -> Result<Vec<Uuid>> {
+    let uuids = ids
+        .iter()
+        .map(|id| Uuid::from_str(id))
+        .collect::<Result<Vec<Uuid>, _>>()?;
+
+    Ok(uuids)
+}
@@ -0,0 +1,35 @@
+use super::Series;
+use crate::{
+    authorization::graphql::{Action, Resource, RoleGuard},
+    graphql::context::get_conn_from_context,
+    series::SeriesRepository,
+};
+use async_graphql::{Context, Object, Result};
+
+#[derive(Default)]
+pub struct SeriesQuery;
+
+#[Object]
+impl SeriesQuery {
+    #[graphql(guard = "RoleGuard::new(Resource::Series, Action::Read)")]
+    pub async fn series(&self, ctx: &Context<'_>, slug: String) -> Result<Option<Series>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let series = SeriesRepository::find_by_slug(conn, &slug).await?;
+
+        Ok(series.map(Series::from))
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Series, Action::Read)")]
+    pub async fn all_series(&self, ctx: &Context<'_>) -> Result<Vec<Series>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let series = SeriesRepository::find_all(conn)
+            .await?
+            .into_iter()
+            .map(Series::from)
+            .collect();
+
+        Ok(series)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod graphql;
+mod repository;
+
+pub use repository::*;
@@ -0,0 +1,327 @@
+use crate::utils::{
+    datetime::get_now,
+    slug::{slugify, with_suffix},
+    uuid::get_uuid_bytes,
+};
+use entity::{
+    posts::{self, Entity as Post},
+    series::{self, Entity as Series},
+    series_posts::{self, Entity as SeriesPost},
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+    Set, TransactionTrait,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+pub struct NewSeriesInput {
+    pub title: String,
+    pub slug: Option<String>,
+    pub description: Option<String>,
+    /// Parts of the series, in reading order.
+    pub posts: Vec<Uuid>,
+    pub created_by: Uuid,
+}
+
+pub struct UpdateSeriesInput {
+    pub uuid: Uuid,
+    pub title: String,
+    pub slug: Option<String>,
+    pub description: Option<String>,
+    /// Parts of the series, in reading order. Posts left out are no longer part of it.
+    pub posts: Vec<Uuid>,
+}
+
+pub struct SeriesRepository;
+
+impl SeriesRepository {
+    pub async fn create(conn: &DatabaseConnection, input: NewSeriesInput) -> Result<series::Model> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let slug = SeriesRepository::resolve_slug(&txn, &input.title, input.slug, None).await?;
+
+        let series = series::ActiveModel {
+            title: Set(input.title),
+            slug: Set(slug),
+            description: Set(clean_description(input.description)),
+            created_by: Set(input.created_by.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let result = Series::insert(series)
+            .exec(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let uuid = Uuid::from_bytes(get_uuid_bytes(&result.last_insert_id));
+        SeriesRepository::set_posts(&txn, uuid, input.posts).await?;
+
+        let series = SeriesRepository::find_by_id(&txn, uuid)
+            .await?
+            .context(SeriesNotFoundSnafu { uuid })?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(series)
+    }
+
+    pub async fn update(
+        conn: &DatabaseConnection,
+        input: UpdateSeriesInput,
+    ) -> Result<series::Model> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let series = SeriesRepository::find_by_id(&txn, input.uuid)
+            .await?
+            .context(SeriesNotFoundSnafu { uuid: input.uuid })?;
+
+        let slug = match (input.slug, series.title == input.title) {
+            (None, true) => series.slug.clone(),
+            (slug, _) => {
+                SeriesRepository::resolve_slug(&txn, &input.title, slug, Some(input.uuid)).await?
+            }
+        };
+
+        let mut series: series::ActiveModel = series.into();
+        series.title = Set(input.title);
+        series.slug = Set(slug);
+        series.description = Set(clean_description(input.description));
+        series.updated_at = Set(get_now());
+
+        let series = series.update(&txn).await.context(QueryFailedSnafu)?;
+        SeriesRepository::set_posts(&txn, input.uuid, input.posts).await?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(series)
+    }
+
+    /// The posts stay around, they just stop being part of a series.
+    pub async fn delete(conn: &DatabaseConnection, uuid: Uuid) -> Result<()> {
+        let result = Series::delete_by_id(uuid.as_bytes().to_vec())
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        ensure!(result.rows_affected > 0, SeriesNotFoundSnafu { uuid });
+
+        Ok(())
+    }
+
+    pub async fn find_by_id<C>(conn: &C, uuid: Uuid) -> Result<Option<series::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        Series::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    pub async fn find_by_slug(
+        conn: &DatabaseConnection,
+        slug: &str,
+    ) -> Result<Option<series::Model>> {
+        Series::find()
+            .filter(series::Column::Slug.eq(slug))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    pub async fn find_all(conn: &DatabaseConnection) -> Result<Vec<series::Model>> {
+        Series::find()
+            .order_by_asc(series::Column::Title)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Parts of the series in reading order, whatever their status.
+    pub async fn find_posts(conn: &DatabaseConnection, uuid: Uuid) -> Result<Vec<posts::Model>> {
+        Post::find()
+            .join(
+                JoinType::InnerJoin,
+                series_posts::Relation::Posts.def().rev(),
+            )
+            .filter(series_posts::Column::SeriesUuid.eq(uuid.as_bytes().to_vec()))
+            .order_by_asc(series_posts::Column::Position)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// The series a post belongs to, along with all of its parts in reading order.
+    pub async fn find_by_post(
+        conn: &DatabaseConnection,
+        post_uuid: Uuid,
+    ) -> Result<Option<(series::Model, Vec<posts::Model>)>> {
+        let series = Series::find()
+            .join(JoinType::InnerJoin, series::Relation::SeriesPosts.def())
+            .filter(series_posts::Column::PostUuid.eq(post_uuid.as_bytes().to_vec()))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let series = match series {
+            Some(series) => series,
+            None => return Ok(None),
+        };
+
+        let uuid = Uuid::from_bytes(series.uuid());
+        let posts = SeriesRepository::find_posts(conn, uuid).await?;
+
+        Ok(Some((series, posts)))
+    }
+
+    /// Replaces the parts of a series. A post can only be part of a single series, so posts
+    /// that already belong to another one are refused.
+    async fn set_posts<C>(conn: &C, uuid: Uuid, posts: Vec<Uuid>) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let mut parts: Vec<Uuid> = Vec::with_capacity(posts.len());
+        for post in posts {
+            if !parts.contains(&post) {
+                parts.push(post);
+            }
+        }
+
+        let ids: Vec<Vec<u8>> = parts.iter().map(|p| p.as_bytes().to_vec()).collect();
+
+        let existing = Post::find()
+            .filter(posts::Column::Uuid.is_in(ids.clone()))
+            .count(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+        ensure!(existing == parts.len(), MissingPostsSnafu);
+
+        let taken = SeriesPost::find()
+            .filter(series_posts::Column::PostUuid.is_in(ids))
+            .filter(series_posts::Column::SeriesUuid.ne(uuid.as_bytes().to_vec()))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+        if let Some(taken) = taken {
+            let post = Uuid::from_bytes(taken.post_uuid());
+            return Err(Error::PostInAnotherSeries { uuid: post });
+        }
+
+        SeriesPost::delete_many()
+            .filter(series_posts::Column::SeriesUuid.eq(uuid.as_bytes().to_vec()))
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        if parts.is_empty() {
+            return Ok(());
+        }
+
+        let parts: Vec<series_posts::ActiveModel> = parts
+            .into_iter()
+            .enumerate()
+            .map(|(position, post)| series_posts::ActiveModel {
+                series_uuid: Set(uuid.as_bytes().to_vec()),
+                post_uuid: Set(post.as_bytes().to_vec()),
+                position: Set(position as i32),
+                ..Default::default()
+            })
+            .collect();
+
+        SeriesPost::insert_many(parts)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Same rules as the post slugs: an explicit slug must be free, one derived from the title
+    /// gets a numeric suffix until it's unique.
+    async fn resolve_slug<C>(
+        conn: &C,
+        title: &str,
+        slug: Option<String>,
+        current: Option<Uuid>,
+    ) -> Result<String>
+    where
+        C: ConnectionTrait,
+    {
+        let mut query = Series::find();
+        if let Some(uuid) = current {
+            query = query.filter(series::Column::Uuid.ne(uuid.as_bytes().to_vec()));
+        }
+
+        if let Some(raw_slug) = slug {
+            let slug = slugify(&raw_slug);
+            ensure!(!slug.is_empty(), InvalidSlugSnafu { slug: raw_slug });
+
+            let existing = query
+                .filter(series::Column::Slug.eq(slug.clone()))
+                .one(conn)
+                .await
+                .context(QueryFailedSnafu)?;
+
+            return match existing {
+                Some(_) => Err(Error::SlugAlreadyExists { slug }),
+                None => Ok(slug),
+            };
+        }
+
+        let base = match slugify(title) {
+            s if s.is_empty() => String::from("series"),
+            s => s,
+        };
+
+        let taken: Vec<String> = query
+            .filter(
+                Condition::any()
+                    .add(series::Column::Slug.eq(base.clone()))
+                    .add(series::Column::Slug.like(&format!("{}-%", base))),
+            )
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .into_iter()
+            .map(|s| s.slug)
+            .collect();
+
+        let mut suffix = 1;
+        let mut slug = base.clone();
+        while taken.contains(&slug) {
+            suffix += 1;
+            slug = with_suffix(&base, suffix);
+        }
+
+        Ok(slug)
+    }
+}
+
+fn clean_description(description: Option<String>) -> Option<String> {
+    description
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Series Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Series with uuid {} not found", uuid))]
+    SeriesNotFound { uuid: Uuid },
+
+    #[snafu(display("Some of the posts of the series don't exist"))]
+    MissingPosts,
+
+    #[snafu(display("Post with uuid {} is already part of another series", uuid))]
+    PostInAnotherSeries { uuid: Uuid },
+
+    #[snafu(display("Slug '{}' is not valid", slug))]
+    InvalidSlug { slug: String },
+
+    #[snafu(display("Slug '{}' is already used by another series", slug))]
+    SlugAlreadyExists { slug: String },
+}
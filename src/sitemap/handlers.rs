@@ -0,0 +1,108 @@
+use super::{Error as SitemapError, RobotsConfig, Sitemap};
+use crate::{db::Db, utils::site::SiteConfig};
+use rocket::{
+    http::{ContentType, Status},
+    request::Request,
+    response::{self, Responder, Response},
+    serde::{
+        ser::{SerializeStruct, Serializer},
+        Serialize,
+    },
+    State,
+};
+use sea_orm_rocket::Connection;
+use snafu::prelude::*;
+use std::io::Cursor;
+
+/// The only sitemap while it fits, otherwise the index of the paginated ones.
+#[get("/sitemap.xml")]
+pub async fn get_sitemap(
+    conn: Connection<'_, Db>,
+    site: &State<SiteConfig>,
+) -> Result<(ContentType, String)> {
+    let sitemap = Sitemap::load(conn.into_inner(), site)
+        .await
+        .context(FailedToLoadSitemapSnafu)?;
+
+    let xml = match sitemap.is_index() {
+        true => sitemap.index_xml(site),
+        false => sitemap.page_xml(1).context(MissingPageSnafu)?,
+    };
+
+    Ok((ContentType::XML, xml))
+}
+
+/// A single page of the sitemap, e.g. `/sitemaps/2.xml`.
+#[get("/sitemaps/<file>")]
+pub async fn get_sitemap_page(
+    file: String,
+    conn: Connection<'_, Db>,
+    site: &State<SiteConfig>,
+) -> Result<(ContentType, String)> {
+    let page: usize = file
+        .strip_suffix(".xml")
+        .and_then(|page| page.parse().ok())
+        .context(MissingPageSnafu)?;
+
+    let sitemap = Sitemap::load(conn.into_inner(), site)
+        .await
+        .context(FailedToLoadSitemapSnafu)?;
+    let xml = sitemap.page_xml(page).context(MissingPageSnafu)?;
+
+    Ok((ContentType::XML, xml))
+}
+
+#[get("/robots.txt")]
+pub async fn get_robots(site: &State<SiteConfig>, robots: &State<RobotsConfig>) -> String {
+    robots.render(&site.site_url("/sitemap.xml"))
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("{}", source))]
+    FailedToLoadSitemap { source: SitemapError },
+
+    #[snafu(display("Sitemap page does not exist"))]
+    MissingPage,
+}
+
+#[rocket::async_trait]
+impl<'r> Responder<'r, 'static> for Error {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let status: Status = match &self {
+            Error::FailedToLoadSitemap { source: _ } => Status::InternalServerError,
+            Error::MissingPage => Status::NotFound,
+        };
+
+        let serialized = serde_json::to_string(&self).unwrap();
+
+        Response::build()
+            .status(status)
+            .sized_body(serialized.len(), Cursor::new(serialized))
+            .header(ContentType::JSON)
+            .ok()
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let kind: String = match self {
+            Error::FailedToLoadSitemap { source: err } => {
+                debug!("Failed to load sitemap {:?}", err);
+
+                String::from("FailedToLoadSitemap")
+            }
+            Error::MissingPage => String::from("MissingPage"),
+        };
+
+        let mut state = serializer.serialize_struct("Error", 2)?;
+        state.serialize_field("kind", &kind)?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
@@ -0,0 +1,8 @@
+pub mod handlers;
+mod repository;
+mod robots;
+mod urls;
+
+pub use repository::{Error as RepositoryError, SitemapPostRow, SitemapRepository, SitemapRow};
+pub use robots::*;
+pub use urls::*;
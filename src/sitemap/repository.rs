@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use entity::{
+    enums::Status,
+    post_authors, post_tags,
+    posts::{self, Entity as Post},
+    tags::{self, Entity as Tag},
+    users::{self, Entity as User},
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, FromQueryResult,
+    JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+};
+use snafu::prelude::*;
+
+/// A page of the site and the last time its content changed.
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct SitemapRow {
+    pub key: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A published post, with what's needed to point to its translations.
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct SitemapPostRow {
+    pub slug: String,
+    pub locale: String,
+    pub translation_group: Option<Vec<u8>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct SitemapRepository;
+
+impl SitemapRepository {
+    /// Published posts, oldest first so new posts don't shift the pages around.
+    pub async fn find_posts(conn: &DatabaseConnection) -> Result<Vec<SitemapPostRow>> {
+        Post::find()
+            .select_only()
+            .column(posts::Column::Slug)
+            .column(posts::Column::Locale)
+            .column(posts::Column::TranslationGroup)
+            .column(posts::Column::UpdatedAt)
+            .filter(posts::Column::Status.eq(Status::Published.to_string()))
+            .order_by_asc(posts::Column::PublishedAt)
+            .order_by_asc(posts::Column::Uuid)
+            .into_model::<SitemapPostRow>()
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Tags with at least one published post, dated by their latest post.
+    pub async fn find_tags(conn: &DatabaseConnection) -> Result<Vec<SitemapRow>> {
+        Tag::find()
+            .select_only()
+            .column_as(tags::Column::Slug, "key")
+            .column_as(
+                Expr::tbl(posts::Entity, posts::Column::UpdatedAt).max(),
+                "updated_at",
+            )
+            .join(JoinType::InnerJoin, tags::Relation::PostTags.def())
+            .join(JoinType::InnerJoin, post_tags::Relation::Posts.def())
+            .filter(posts::Column::Status.eq(Status::Published.to_string()))
+            .group_by(tags::Column::Slug)
+            .order_by_asc(tags::Column::Slug)
+            .into_model::<SitemapRow>()
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Users with a public profile credited on at least one published post, keyed by handle
+    /// and dated by their latest post.
+    pub async fn find_authors(conn: &DatabaseConnection) -> Result<Vec<SitemapRow>> {
+        User::find()
+            .select_only()
+            .column_as(users::Column::Handle, "key")
+            .column_as(
+                Expr::tbl(posts::Entity, posts::Column::UpdatedAt).max(),
+                "updated_at",
+            )
+            .join(JoinType::InnerJoin, users::Relation::PostAuthors.def())
+            .join(JoinType::InnerJoin, post_authors::Relation::Posts.def())
+            .filter(users::Column::Handle.is_not_null())
+            .filter(posts::Column::Status.eq(Status::Published.to_string()))
+            .group_by(users::Column::Handle)
+            .order_by_asc(users::Column::Handle)
+            .into_model::<SitemapRow>()
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Sitemap Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+}
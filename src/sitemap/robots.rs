@@ -0,0 +1,37 @@
+use std::{env, fmt::Write};
+
+/// Paths crawlers should stay away from, read from the comma separated `ROBOTS_DISALLOW`
+/// variable. Disallowing `/` hides the whole site, which is useful for staging.
+#[derive(Debug, Clone, Default)]
+pub struct RobotsConfig {
+    pub disallow: Vec<String>,
+}
+
+impl RobotsConfig {
+    pub fn from_env() -> Self {
+        let disallow = env::var("ROBOTS_DISALLOW")
+            .unwrap_or_else(|_| String::from(""))
+            .split(',')
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .collect();
+
+        RobotsConfig { disallow }
+    }
+
+    pub fn render(&self, sitemap_url: &str) -> String {
+        let mut robots = String::from("User-agent: *\n");
+
+        // An empty Disallow means everything can be crawled.
+        if self.disallow.is_empty() {
+            robots.push_str("Disallow:\n");
+        }
+        for path in &self.disallow {
+            let _ = writeln!(robots, "Disallow: {}", path);
+        }
+
+        let _ = writeln!(robots, "\nSitemap: {}", sitemap_url);
+
+        robots
+    }
+}
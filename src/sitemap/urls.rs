@@ -0,0 +1,165 @@
+use super::{RepositoryError, SitemapRepository};
+use crate::utils::{locale::Alternate, site::SiteConfig, xml::escape};
+use chrono::{DateTime, SecondsFormat, Utc};
+use sea_orm::DatabaseConnection;
+use snafu::prelude::*;
+use std::fmt::Write;
+
+/// Limit set by the sitemaps protocol, bigger sitemaps get split behind a sitemap index.
+pub const MAX_URLS_PER_SITEMAP: usize = 50_000;
+
+#[derive(Debug, Clone)]
+pub struct SitemapUrl {
+    pub loc: String,
+    pub lastmod: DateTime<Utc>,
+    /// Every language version of the page, itself included, as the protocol asks.
+    pub alternates: Vec<Alternate>,
+}
+
+/// Every public page of the site: posts first, then tags and authors.
+#[derive(Debug, Clone)]
+pub struct Sitemap {
+    urls: Vec<SitemapUrl>,
+}
+
+impl Sitemap {
+    pub async fn load(conn: &DatabaseConnection, site: &SiteConfig) -> Result<Sitemap> {
+        let posts = SitemapRepository::find_posts(conn)
+            .await
+            .context(FailedToLoadUrlsSnafu)?;
+        let tags = SitemapRepository::find_tags(conn)
+            .await
+            .context(FailedToLoadUrlsSnafu)?;
+        let authors = SitemapRepository::find_authors(conn)
+            .await
+            .context(FailedToLoadUrlsSnafu)?;
+
+        let versions: Vec<(Vec<u8>, Alternate)> = posts
+            .iter()
+            .filter_map(|row| {
+                let group = row.translation_group.clone()?;
+                let alternate = Alternate {
+                    locale: row.locale.clone(),
+                    url: site.post_url(&row.slug),
+                };
+
+                Some((group, alternate))
+            })
+            .collect();
+
+        let posts = posts.into_iter().map(|row| {
+            let alternates = match &row.translation_group {
+                Some(group) => versions
+                    .iter()
+                    .filter(|(g, _)| g == group)
+                    .map(|(_, alternate)| alternate.clone())
+                    .collect(),
+                None => vec![],
+            };
+
+            SitemapUrl {
+                loc: site.post_url(&row.slug),
+                lastmod: row.updated_at,
+                alternates,
+            }
+        });
+        let tags = tags.into_iter().map(|row| SitemapUrl {
+            loc: site.tag_url(&row.key),
+            lastmod: row.updated_at,
+            alternates: vec![],
+        });
+        let authors = authors.into_iter().map(|row| SitemapUrl {
+            loc: site.author_url(&row.key),
+            lastmod: row.updated_at,
+            alternates: vec![],
+        });
+
+        Ok(Sitemap {
+            urls: posts.chain(tags).chain(authors).collect(),
+        })
+    }
+
+    pub fn is_index(&self) -> bool {
+        self.urls.len() > MAX_URLS_PER_SITEMAP
+    }
+
+    pub fn pages(&self) -> usize {
+        let urls = self.urls.len();
+
+        (urls / MAX_URLS_PER_SITEMAP + usize::from(urls % MAX_URLS_PER_SITEMAP != 0)).max(1)
+    }
+
+    /// The `urlset` of a single page, pages start at 1.
+    pub fn page_xml(&self, page: usize) -> Option<String> {
+        if page == 0 || page > self.pages() {
+            return None;
+        }
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(
+            "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" \
+             xmlns:xhtml=\"http://www.w3.org/1999/xhtml\">\n",
+        );
+
+        for url in self.chunk(page) {
+            let _ = write!(
+                xml,
+                "<url><loc>{}</loc><lastmod>{}</lastmod>",
+                escape(&url.loc),
+                w3c_date(url.lastmod)
+            );
+            for alternate in &url.alternates {
+                let _ = write!(
+                    xml,
+                    "<xhtml:link rel=\"alternate\" hreflang=\"{}\" href=\"{}\"/>",
+                    escape(&alternate.locale),
+                    escape(&alternate.url)
+                );
+            }
+            xml.push_str("</url>\n");
+        }
+
+        xml.push_str("</urlset>\n");
+
+        Some(xml)
+    }
+
+    /// Points to every page, each one dated by its most recent URL. Crawlers only accept sitemaps
+    /// on the same host as the URLs they list, so the pages are linked through the site.
+    pub fn index_xml(&self, site: &SiteConfig) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+
+        for page in 1..=self.pages() {
+            let loc = site.site_url(&format!("/sitemaps/{}.xml", page));
+            let _ = write!(xml, "<sitemap><loc>{}</loc>", escape(&loc));
+            if let Some(lastmod) = self.chunk(page).iter().map(|url| url.lastmod).max() {
+                let _ = write!(xml, "<lastmod>{}</lastmod>", w3c_date(lastmod));
+            }
+            xml.push_str("</sitemap>\n");
+        }
+
+        xml.push_str("</sitemapindex>\n");
+
+        xml
+    }
+
+    fn chunk(&self, page: usize) -> &[SitemapUrl] {
+        let start = ((page - 1) * MAX_URLS_PER_SITEMAP).min(self.urls.len());
+        let end = (start + MAX_URLS_PER_SITEMAP).min(self.urls.len());
+
+        &self.urls[start..end]
+    }
+}
+
+fn w3c_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Failed to load the sitemap: {}", source))]
+    FailedToLoadUrls { source: RepositoryError },
+}
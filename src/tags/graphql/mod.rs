@@ -1,25 +1,180 @@
-use async_graphql::{SimpleObject, ID};
-use entity::tags;
-use std::convert::From;
+use crate::{
+    graphql::context::{get_conn_from_context, get_renderer_from_context},
+    posts::{
+        graphql::{published_connection, PostsOrder, PublishedPost},
+        PostsFilter,
+    },
+    tags::{
+        TagHierarchyRepository, TagSynonymsRepository, TagUsage as RepoTagUsage, TagsRepository,
+    },
+    utils::pagination::Cursor,
+};
+use async_graphql::{
+    connection::{Connection, EmptyFields},
+    ComplexObject, Context, Result, SimpleObject, ID,
+};
+use entity::{enums::Status, tag_synonyms, tags};
+use std::{convert::From, str::FromStr};
 use uuid::Uuid;
 
 mod loader;
+mod mutation;
+mod query;
 
 pub use loader::*;
+pub use mutation::*;
+pub use query::*;
 
 #[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
 pub struct Tag {
     pub uuid: ID,
     pub name: String,
+    /// Where the page of the tag lives on the site.
+    pub slug: String,
+    /// Markdown.
+    pub description: Option<String>,
+    #[graphql(skip)]
+    parent_uuid: Option<Uuid>,
+}
+
+/// A tag along with how many posts carry it.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct TagUsage {
+    pub tag: Tag,
+    /// Posts carrying the tag, whatever their status.
+    pub post_count: i64,
+    pub published_post_count: i64,
+}
+
+/// Another way of writing a tag, posts given the alias get the tag instead.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct TagSynonym {
+    pub uuid: ID,
+    /// Stored lowercase, with its spacing normalized.
+    pub alias: String,
+}
+
+#[ComplexObject]
+impl Tag {
+    async fn description_html(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let renderer = get_renderer_from_context(ctx).await?;
+
+        Ok(self.description.as_deref().map(|d| renderer.render(d)))
+    }
+
+    async fn parent(&self, ctx: &Context<'_>) -> Result<Option<Tag>> {
+        let uuid = match self.parent_uuid {
+            Some(uuid) => uuid,
+            None => return Ok(None),
+        };
+
+        let conn = get_conn_from_context(ctx).await?;
+        let parent = TagsRepository::find_by_id(conn, uuid).await?;
+
+        Ok(parent.map(Tag::from))
+    }
+
+    async fn children(&self, ctx: &Context<'_>) -> Result<Vec<Tag>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        let children = TagHierarchyRepository::find_children(conn, uuid)
+            .await?
+            .into_iter()
+            .map(Tag::from)
+            .collect();
+
+        Ok(children)
+    }
+
+    /// From the top of the hierarchy down to the parent, for breadcrumbs.
+    async fn ancestors(&self, ctx: &Context<'_>) -> Result<Vec<Tag>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        let ancestors = TagHierarchyRepository::find_ancestors(conn, uuid)
+            .await?
+            .into_iter()
+            .map(Tag::from)
+            .collect();
+
+        Ok(ancestors)
+    }
+
+    /// Published posts carrying the tag, for the page of the tag.
+    #[allow(clippy::too_many_arguments)]
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+        include_subtags: Option<bool>,
+        order_by: Option<PostsOrder>,
+    ) -> Result<Connection<Cursor, PublishedPost, EmptyFields, EmptyFields>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let filter = PostsFilter {
+            status: Some(Status::Published),
+            tagged: Some(Uuid::from_str(&self.uuid)?),
+            include_subtags: include_subtags.unwrap_or_default(),
+            ..Default::default()
+        };
+
+        published_connection(conn, filter, order_by, after, before, first, last).await
+    }
+}
+
+#[ComplexObject]
+impl TagUsage {
+    async fn synonyms(&self, ctx: &Context<'_>) -> Result<Vec<TagSynonym>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.tag.uuid)?;
+
+        let synonyms = TagSynonymsRepository::find_by_tag(conn, uuid)
+            .await?
+            .into_iter()
+            .map(TagSynonym::from)
+            .collect();
+
+        Ok(synonyms)
+    }
 }
 
 impl From<tags::Model> for Tag {
     fn from(tag: tags::Model) -> Self {
         let uuid = Uuid::from_bytes(tag.uuid());
+        let parent_uuid = tag.parent_uuid().map(Uuid::from_bytes);
 
         Tag {
             uuid: uuid.into(),
             name: tag.name,
+            slug: tag.slug,
+            description: tag.description,
+            parent_uuid,
+        }
+    }
+}
+
+impl From<(tags::Model, RepoTagUsage)> for TagUsage {
+    fn from((tag, usage): (tags::Model, RepoTagUsage)) -> Self {
+        TagUsage {
+            tag: tag.into(),
+            post_count: usage.posts,
+            published_post_count: usage.published_posts,
+        }
+    }
+}
+
+impl From<tag_synonyms::Model> for TagSynonym {
+    fn from(synonym: tag_synonyms::Model) -> Self {
+        let uuid = Uuid::from_bytes(synonym.uuid());
+
+        TagSynonym {
+            uuid: uuid.into(),
+            alias: synonym.alias,
         }
     }
 }
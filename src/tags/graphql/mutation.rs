@@ -0,0 +1,130 @@
+use super::{Tag, TagSynonym};
+use crate::{
+    authorization::graphql::{Action, Resource, RoleGuard},
+    builds::coordinator::notify_change,
+    graphql::context::get_conn_from_context,
+    tags::{
+        TagHierarchyRepository, TagSynonymsRepository, TagsRepository,
+        UpdateTagInput as UpdateTagRepoInput,
+    },
+};
+use async_graphql::{Context, InputObject, Object, Result, ID};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(InputObject)]
+pub struct UpdateTagInput {
+    pub uuid: ID,
+    /// Keeps the current slug when left empty.
+    pub slug: Option<String>,
+    /// Markdown.
+    pub description: Option<String>,
+}
+
+#[derive(Default)]
+pub struct TagsMutation;
+
+#[Object]
+impl TagsMutation {
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Write)")]
+    pub async fn rename_tag(&self, ctx: &Context<'_>, uuid: String, name: String) -> Result<Tag> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&uuid)?;
+
+        let tag = TagsRepository::rename(conn, uuid, &name).await?;
+        notify_change(conn, "Tag renamed").await;
+
+        Ok(tag.into())
+    }
+
+    /// Changes the page of a tag, its name is changed with `renameTag`.
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Write)")]
+    pub async fn update_tag(&self, ctx: &Context<'_>, input: UpdateTagInput) -> Result<Tag> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let input = UpdateTagRepoInput {
+            uuid: Uuid::from_str(&input.uuid)?,
+            slug: input.slug,
+            description: input.description,
+        };
+
+        let tag = TagsRepository::update(conn, input).await?;
+        notify_change(conn, "Tag updated").await;
+
+        Ok(tag.into())
+    }
+
+    /// Moves a tag below another one, or back to the top when no parent is given.
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Write)")]
+    pub async fn set_tag_parent(
+        &self,
+        ctx: &Context<'_>,
+        uuid: String,
+        parent_uuid: Option<String>,
+    ) -> Result<Tag> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&uuid)?;
+        let parent = match parent_uuid {
+            Some(parent) => Some(Uuid::from_str(&parent)?),
+            None => None,
+        };
+
+        let tag = TagHierarchyRepository::set_parent(conn, uuid, parent).await?;
+        notify_change(conn, "Tag moved").await;
+
+        Ok(tag.into())
+    }
+
+    /// Posts given `alias` as a tag get the tag instead from now on, posts already tagged stay
+    /// as they are.
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Write)")]
+    pub async fn add_tag_synonym(
+        &self,
+        ctx: &Context<'_>,
+        uuid: String,
+        alias: String,
+    ) -> Result<TagSynonym> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&uuid)?;
+
+        let synonym = TagSynonymsRepository::add(conn, uuid, &alias).await?;
+
+        Ok(synonym.into())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Write)")]
+    pub async fn remove_tag_synonym(&self, ctx: &Context<'_>, uuid: String) -> Result<ID> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&uuid)?;
+
+        TagSynonymsRepository::remove(conn, uuid).await?;
+
+        Ok(uuid.into())
+    }
+
+    /// Moves the posts of `from` over to `into`, then deletes `from`. Its name and synonyms
+    /// become synonyms of `into`.
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Write)")]
+    pub async fn merge_tags(&self, ctx: &Context<'_>, from: String, into: String) -> Result<Tag> {
+        let conn = get_conn_from_context(ctx).await?;
+        let from = Uuid::from_str(&from)?;
+        let into = Uuid::from_str(&into)?;
+
+        let tag = TagsRepository::merge(conn, from, into).await?;
+        notify_change(conn, "Tags merged").await;
+
+        Ok(tag.into())
+    }
+
+    /// Removes the tag from every post carrying it.
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Write)")]
+    pub async fn delete_tag(&self, ctx: &Context<'_>, uuid: String) -> Result<ID> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&uuid)?;
+
+        TagsRepository::delete(conn, uuid).await?;
+        notify_change(conn, "Tag deleted").await;
+
+        Ok(uuid.into())
+    }
+}
@@ -0,0 +1,46 @@
+use super::TagUsage;
+use crate::{
+    authorization::graphql::{Action, Resource, RoleGuard},
+    graphql::context::get_conn_from_context,
+    tags::TagsRepository,
+};
+use async_graphql::{Context, Object, Result};
+
+#[derive(Default)]
+pub struct TagsQuery;
+
+#[Object]
+impl TagsQuery {
+    /// Every tag by name, including the ones no post carries anymore.
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Read)")]
+    pub async fn tags(&self, ctx: &Context<'_>) -> Result<Vec<TagUsage>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let tags = TagsRepository::find_all(conn).await?;
+        let tags = TagsRepository::with_usage(conn, tags)
+            .await?
+            .into_iter()
+            .map(TagUsage::from)
+            .collect();
+
+        Ok(tags)
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Tag, Action::Read)")]
+    pub async fn tag(&self, ctx: &Context<'_>, slug: String) -> Result<Option<TagUsage>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let tag = match TagsRepository::find_by_slug(conn, &slug).await? {
+            Some(tag) => tag,
+            None => return Ok(None),
+        };
+
+        let tag = TagsRepository::with_usage(conn, vec![tag])
+            .await?
+            .into_iter()
+            .next()
+            .map(TagUsage::from);
+
+        Ok(tag)
+    }
+}
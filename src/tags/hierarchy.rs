@@ -0,0 +1,126 @@
+use crate::utils::datetime::get_now;
+use entity::tags::{self, Entity as Tag};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+pub struct TagHierarchyRepository;
+
+impl TagHierarchyRepository {
+    /// Moves a tag below another one, or to the top when there's no parent. A tag can't end up
+    /// below itself, directly or through its descendants.
+    pub async fn set_parent(
+        conn: &DatabaseConnection,
+        uuid: Uuid,
+        parent: Option<Uuid>,
+    ) -> Result<tags::Model> {
+        // The cycle check and the move go together, or two opposite moves could both pass it.
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let tag = TagHierarchyRepository::find_tag(&txn, uuid).await?;
+
+        if let Some(parent) = parent {
+            ensure!(parent != uuid, CyclicParentSnafu { uuid });
+
+            let ancestors = TagHierarchyRepository::find_ancestors(&txn, parent).await?;
+            ensure!(
+                ancestors.iter().all(|a| a.uuid != tag.uuid),
+                CyclicParentSnafu { uuid }
+            );
+        }
+
+        let mut tag: tags::ActiveModel = tag.into();
+        tag.parent_uuid = Set(parent.map(|p| p.as_bytes().to_vec()));
+        tag.updated_at = Set(get_now());
+
+        let tag = tag.update(&txn).await.context(QueryFailedSnafu)?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(tag)
+    }
+
+    pub async fn find_children(conn: &DatabaseConnection, uuid: Uuid) -> Result<Vec<tags::Model>> {
+        Tag::find()
+            .filter(tags::Column::ParentUuid.eq(uuid.as_bytes().to_vec()))
+            .order_by_asc(tags::Column::Name)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// The tags above the given one, from the top of the hierarchy down to its parent.
+    pub async fn find_ancestors<C>(conn: &C, uuid: Uuid) -> Result<Vec<tags::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        let mut current = TagHierarchyRepository::find_tag(conn, uuid).await?;
+        let mut ancestors: Vec<tags::Model> = vec![];
+
+        while let Some(parent_uuid) = current.parent_uuid.clone() {
+            // Only reachable if the hierarchy was edited by hand, stop rather than loop forever.
+            if parent_uuid == current.uuid || ancestors.iter().any(|a| a.uuid == parent_uuid) {
+                break;
+            }
+
+            let parent = Tag::find_by_id(parent_uuid)
+                .one(conn)
+                .await
+                .context(QueryFailedSnafu)?;
+
+            match parent {
+                Some(parent) => {
+                    ancestors.push(parent.clone());
+                    current = parent;
+                }
+                None => break,
+            }
+        }
+
+        ancestors.reverse();
+
+        Ok(ancestors)
+    }
+
+    /// Gives the children of a tag to another parent, before the tag itself goes away.
+    pub async fn move_children<C>(conn: &C, from: &[u8], to: Option<Vec<u8>>) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        Tag::update_many()
+            .col_expr(tags::Column::ParentUuid, Expr::value(to))
+            .filter(tags::Column::ParentUuid.eq(from.to_vec()))
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    async fn find_tag<C>(conn: &C, uuid: Uuid) -> Result<tags::Model>
+    where
+        C: ConnectionTrait,
+    {
+        Tag::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .context(TagNotFoundSnafu { uuid })
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Tag Hierarchy Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Tag with uuid {} not found", uuid))]
+    TagNotFound { uuid: Uuid },
+
+    #[snafu(display("Tag {} can't be placed below itself", uuid))]
+    CyclicParent { uuid: Uuid },
+}
@@ -1,4 +1,9 @@
 pub mod graphql;
+mod hierarchy;
 mod repository;
+mod synonyms;
 
+pub use hierarchy::{Error as HierarchyError, TagHierarchyRepository};
+pub use migration::tag_names::{name_key, normalize_name};
 pub use repository::*;
+pub use synonyms::{Error as SynonymsError, TagSynonymsRepository};
@@ -1,16 +1,52 @@
-use crate::{tags::graphql::PostTagUuid, utils::vec::vec_diff};
+use crate::{
+    posts::{RelatedError, RelatedPostsRepository},
+    tags::{
+        graphql::PostTagUuid, name_key, normalize_name, HierarchyError, SynonymsError,
+        TagHierarchyRepository, TagSynonymsRepository,
+    },
+    utils::{
+        datetime::get_now,
+        slug::{slugify, with_suffix},
+        vec::vec_diff,
+    },
+};
 use entity::{
-    post_tags::Relation as PostTagsRelation,
+    enums::Status,
+    post_tags::{self, Entity as PostTag, Relation as PostTagsRelation},
     posts::{Column as PostColumn, Entity as PostEntity, Model as Post},
     tags::{self, Entity as Tag},
 };
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter, QuerySelect,
-    RelationTrait, Set,
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    DbErr, EntityTrait, FromQueryResult, JoinType, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait, RelationTrait, Set, TransactionTrait,
 };
 use snafu::prelude::*;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// How many posts carry a tag, whatever their status, and how many of them are published.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TagUsage {
+    pub posts: i64,
+    pub published_posts: i64,
+}
+
+pub struct UpdateTagInput {
+    pub uuid: Uuid,
+    /// Keeps the current slug when left empty.
+    pub slug: Option<String>,
+    /// Markdown, rendered when it's read.
+    pub description: Option<String>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct TagUsageRow {
+    tag_uuid: Vec<u8>,
+    posts: i64,
+    published_posts: i64,
+}
+
 pub struct TagsRepository;
 
 impl TagsRepository {
@@ -53,34 +89,334 @@ impl TagsRepository {
         Ok(post_tags)
     }
 
-    pub async fn find_or_create_tags(
+    pub async fn find_by_id<C>(conn: &C, uuid: Uuid) -> Result<Option<tags::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        Tag::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    pub async fn find_by_slug(
+        conn: &DatabaseConnection,
+        slug: &str,
+    ) -> Result<Option<tags::Model>> {
+        Tag::find()
+            .filter(tags::Column::Slug.eq(slug))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    pub async fn find_all(conn: &DatabaseConnection) -> Result<Vec<tags::Model>> {
+        Tag::find()
+            .order_by_asc(tags::Column::Name)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Pairs each tag with its usage, tags no post carries anymore get zeroes.
+    pub async fn with_usage(
         conn: &DatabaseConnection,
-        tags: Vec<String>,
-    ) -> Result<Vec<tags::Model>> {
-        // 1. Check wether the given tags need to be created or not to the DB
-        let tags_ref: Vec<&str> = tags.iter().map(|t| t.as_str()).collect();
+        tags: Vec<tags::Model>,
+    ) -> Result<Vec<(tags::Model, TagUsage)>> {
+        let ids: Vec<Vec<u8>> = tags.iter().map(|tag| tag.uuid.clone()).collect();
+
+        let mut usage: HashMap<Vec<u8>, TagUsage> = PostTag::find()
+            .select_only()
+            .column(post_tags::Column::TagUuid)
+            .column_as(
+                Expr::tbl(post_tags::Entity, post_tags::Column::Uuid).count(),
+                "posts",
+            )
+            .column_as(
+                Expr::cust_with_values(
+                    "SUM(CASE WHEN `posts`.`status` = ? THEN 1 ELSE 0 END)",
+                    vec![Status::Published.to_string()],
+                ),
+                "published_posts",
+            )
+            .join(JoinType::InnerJoin, post_tags::Relation::Posts.def())
+            .filter(post_tags::Column::TagUuid.is_in(ids))
+            .group_by(post_tags::Column::TagUuid)
+            .into_model::<TagUsageRow>()
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .into_iter()
+            .map(|row| {
+                let usage = TagUsage {
+                    posts: row.posts,
+                    published_posts: row.published_posts,
+                };
+
+                (row.tag_uuid, usage)
+            })
+            .collect();
+
+        let tags = tags
+            .into_iter()
+            .map(|tag| {
+                let tag_usage = usage.remove(&tag.uuid).unwrap_or_default();
+                (tag, tag_usage)
+            })
+            .collect();
+
+        Ok(tags)
+    }
+
+    /// Names stay unique whatever their case, renaming a tag to the name of another one is
+    /// refused: that's a merge. Same goes for a synonym of another tag.
+    pub async fn rename(conn: &DatabaseConnection, uuid: Uuid, name: &str) -> Result<tags::Model> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let tag = TagsRepository::find_by_id(&txn, uuid)
+            .await?
+            .context(TagNotFoundSnafu { uuid })?;
+
+        let name = normalize_name(name);
+        ensure!(!name.is_empty(), InvalidNameSnafu { name });
+        let key = name_key(&name);
+
+        let existing = Tag::find()
+            .filter(tags::Column::NormalizedName.eq(key.clone()))
+            .filter(tags::Column::Uuid.ne(tag.uuid.clone()))
+            .one(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+        ensure!(existing.is_none(), NameAlreadyTakenSnafu { name });
+
+        let synonym = TagSynonymsRepository::find_by_alias(&txn, &key)
+            .await
+            .context(SynonymsRepoFailedSnafu)?;
+        if let Some(synonym) = synonym {
+            ensure!(synonym.tag_uuid == tag.uuid, NameIsASynonymSnafu { name });
+
+            // The tag is now found by its name, the synonym has nothing left to do.
+            TagSynonymsRepository::remove(&txn, Uuid::from_bytes(synonym.uuid()))
+                .await
+                .context(SynonymsRepoFailedSnafu)?;
+        }
+
+        let mut tag: tags::ActiveModel = tag.into();
+        tag.name = Set(name);
+        tag.normalized_name = Set(key);
+        tag.updated_at = Set(get_now());
+
+        let tag = tag.update(&txn).await.context(QueryFailedSnafu)?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(tag)
+    }
+
+    /// Slugs don't follow renames, tag pages keep their URL unless it's changed here.
+    pub async fn update(conn: &DatabaseConnection, input: UpdateTagInput) -> Result<tags::Model> {
+        let tag = TagsRepository::find_by_id(conn, input.uuid)
+            .await?
+            .context(TagNotFoundSnafu { uuid: input.uuid })?;
+
+        let slug = match input.slug {
+            Some(raw_slug) => {
+                let slug = slugify(&raw_slug);
+                ensure!(!slug.is_empty(), InvalidSlugSnafu { slug: raw_slug });
+
+                let existing = Tag::find()
+                    .filter(tags::Column::Slug.eq(slug.clone()))
+                    .filter(tags::Column::Uuid.ne(tag.uuid.clone()))
+                    .one(conn)
+                    .await
+                    .context(QueryFailedSnafu)?;
+                ensure!(existing.is_none(), SlugAlreadyExistsSnafu { slug });
+
+                slug
+            }
+            None => tag.slug.clone(),
+        };
+
+        let description = input
+            .description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+
+        let mut tag: tags::ActiveModel = tag.into();
+        tag.slug = Set(slug);
+        tag.description = Set(description);
+        tag.updated_at = Set(get_now());
+
+        tag.update(conn).await.context(QueryFailedSnafu)
+    }
+
+    /// Moves the posts of `from` over to `into` and deletes `from`. Posts that already carry
+    /// both end up with `into` just once, the tags below `from` end up below `into`, and the
+    /// name of `from` becomes a synonym of `into`.
+    pub async fn merge(conn: &DatabaseConnection, from: Uuid, into: Uuid) -> Result<tags::Model> {
+        ensure!(from != into, SelfMergeSnafu);
+
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let source = TagsRepository::find_by_id(&txn, from)
+            .await?
+            .context(TagNotFoundSnafu { uuid: from })?;
+        let target = TagsRepository::find_by_id(&txn, into)
+            .await?
+            .context(TagNotFoundSnafu { uuid: into })?;
+
+        RelatedPostsRepository::invalidate_tagged(
+            &txn,
+            vec![source.uuid.clone(), target.uuid.clone()],
+        )
+        .await
+        .context(RelatedRepoFailedSnafu)?;
+
+        let already_tagged = PostTag::find()
+            .select_only()
+            .column(post_tags::Column::PostUuid)
+            .filter(post_tags::Column::TagUuid.eq(target.uuid.clone()))
+            .into_query();
+
+        PostTag::update_many()
+            .col_expr(post_tags::Column::TagUuid, Expr::value(target.uuid.clone()))
+            .filter(post_tags::Column::TagUuid.eq(source.uuid.clone()))
+            .filter(post_tags::Column::PostUuid.not_in_subquery(already_tagged))
+            .exec(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        // A tag merged into one of its descendants leaves it its place in the hierarchy.
+        let ancestors = TagHierarchyRepository::find_ancestors(&txn, into)
+            .await
+            .context(HierarchyRepoFailedSnafu)?;
+        let below_source = ancestors.iter().any(|a| a.uuid == source.uuid);
+
+        let mut target: tags::ActiveModel = target.into();
+        if below_source {
+            target.parent_uuid = Set(source.parent_uuid.clone());
+        }
+        target.updated_at = Set(get_now());
+        let target = target.update(&txn).await.context(QueryFailedSnafu)?;
+
+        TagHierarchyRepository::move_children(&txn, &source.uuid, Some(target.uuid.clone()))
+            .await
+            .context(HierarchyRepoFailedSnafu)?;
+
+        TagSynonymsRepository::absorb(&txn, &source, &target)
+            .await
+            .context(SynonymsRepoFailedSnafu)?;
+
+        // Whatever is left were duplicates, the tag is deleted along with them.
+        Tag::delete_by_id(source.uuid)
+            .exec(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(target)
+    }
+
+    /// The posts lose the tag but stay around, the tags below it move up a level.
+    pub async fn delete(conn: &DatabaseConnection, uuid: Uuid) -> Result<()> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let tag = TagsRepository::find_by_id(&txn, uuid)
+            .await?
+            .context(TagNotFoundSnafu { uuid })?;
+
+        RelatedPostsRepository::invalidate_tagged(&txn, vec![tag.uuid.clone()])
+            .await
+            .context(RelatedRepoFailedSnafu)?;
+
+        TagHierarchyRepository::move_children(&txn, &tag.uuid, tag.parent_uuid.clone())
+            .await
+            .context(HierarchyRepoFailedSnafu)?;
+
+        Tag::delete_by_id(tag.uuid)
+            .exec(&txn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Tags are matched whatever the case and spacing they were typed with, and synonyms are
+    /// replaced with their tag. Tags that still aren't found get created.
+    pub async fn find_or_create_tags<C>(conn: &C, tags: Vec<String>) -> Result<Vec<tags::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        // 1. Normalize the given tags, the same tag typed twice is only kept once.
+        let mut names: Vec<(String, String)> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let name = normalize_name(&tag);
+            let key = name_key(&name);
+
+            if !name.is_empty() && !names.iter().any(|(k, _)| k == &key) {
+                names.push((key, name));
+            }
+        }
+
+        if names.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let keys: Vec<String> = names.iter().map(|(key, _)| key.clone()).collect();
+
+        // 2. Check wether the given tags need to be created or not, synonyms go first.
+        let synonyms = TagSynonymsRepository::resolve(conn, keys.clone())
+            .await
+            .context(SynonymsRepoFailedSnafu)?;
         let existing_tags = Tag::find()
-            .filter(tags::Column::Name.is_in(tags_ref.clone()))
+            .filter(tags::Column::NormalizedName.is_in(keys.clone()))
+            .order_by_asc(tags::Column::CreatedAt)
             .all(conn)
             .await
             .context(QueryFailedSnafu)?;
 
-        let existing_tag_names: Vec<String> =
-            existing_tags.iter().map(|tag| tag.name.clone()).collect();
+        let mut found: Vec<tags::Model> = vec![];
+        let mut found_keys: Vec<String> = vec![];
+        for key in keys.iter() {
+            let tag = synonyms
+                .iter()
+                .find(|(alias, _)| alias == key)
+                .map(|(_, tag)| tag)
+                .or_else(|| existing_tags.iter().find(|t| &t.normalized_name == key));
+
+            if let Some(tag) = tag {
+                found_keys.push(key.clone());
+                if !found.iter().any(|t| t.uuid == tag.uuid) {
+                    found.push(tag.clone());
+                }
+            }
+        }
 
-        // 2.The tags that do not exist yet will be created.
-        let tags_to_create = vec_diff(tags.clone(), existing_tag_names);
+        // 3.The tags that do not exist yet will be created.
+        let keys_to_create = vec_diff(keys, found_keys);
 
         // If there are no tags to create, it means we can skip the create part.
-        if tags_to_create.is_empty() {
-            return Ok(existing_tags);
+        if keys_to_create.is_empty() {
+            return Ok(found);
         }
 
-        // 3. Insert the missing tags to the DB
+        let tags_to_create: Vec<String> = names
+            .into_iter()
+            .filter(|(key, _)| keys_to_create.contains(key))
+            .map(|(_, name)| name)
+            .collect();
+
+        // 4. Insert the missing tags to the DB, each with a slug of its own.
+        let slugs = TagsRepository::free_slugs(conn, &tags_to_create).await?;
         let new_tags: Vec<tags::ActiveModel> = tags_to_create
             .into_iter()
-            .map(|t| tags::ActiveModel {
-                name: Set(t),
+            .zip(slugs)
+            .map(|(name, slug)| tags::ActiveModel {
+                normalized_name: Set(name_key(&name)),
+                name: Set(name),
+                slug: Set(slug),
                 ..Default::default()
             })
             .collect();
@@ -90,12 +426,55 @@ impl TagsRepository {
             .await
             .context(QueryFailedSnafu)?;
 
-        // 4. Query for all the tags again, they now must exist in the DB
-        Tag::find()
-            .filter(tags::Column::Name.is_in(tags_ref))
+        // 5. Query for the created tags, they now must exist in the DB
+        let created = Tag::find()
+            .filter(tags::Column::NormalizedName.is_in(keys_to_create))
             .all(conn)
             .await
-            .context(QueryFailedSnafu)
+            .context(QueryFailedSnafu)?;
+        found.extend(created);
+
+        Ok(found)
+    }
+
+    /// Slugs derived from the names, with a numeric suffix until they collide neither with
+    /// another tag nor with each other.
+    async fn free_slugs<C>(conn: &C, names: &[String]) -> Result<Vec<String>>
+    where
+        C: ConnectionTrait,
+    {
+        let mut taken: Vec<String> = vec![];
+        let mut slugs: Vec<String> = Vec::with_capacity(names.len());
+
+        for name in names {
+            let base = match slugify(name) {
+                s if s.is_empty() => String::from("tag"),
+                s => s,
+            };
+
+            let existing = Tag::find()
+                .filter(
+                    Condition::any()
+                        .add(tags::Column::Slug.eq(base.clone()))
+                        .add(tags::Column::Slug.like(&format!("{}-%", base))),
+                )
+                .all(conn)
+                .await
+                .context(QueryFailedSnafu)?;
+            taken.extend(existing.into_iter().map(|tag| tag.slug));
+
+            let mut suffix = 1;
+            let mut slug = base.clone();
+            while taken.contains(&slug) {
+                suffix += 1;
+                slug = with_suffix(&base, suffix);
+            }
+
+            taken.push(slug.clone());
+            slugs.push(slug);
+        }
+
+        Ok(slugs)
     }
 }
 
@@ -105,4 +484,90 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 pub enum Error {
     #[snafu(display("Tags Query failed: {}", source))]
     QueryFailed { source: DbErr },
+
+    #[snafu(display("Tag with uuid {} not found", uuid))]
+    TagNotFound { uuid: Uuid },
+
+    #[snafu(display("Tag name '{}' is not valid", name))]
+    InvalidName { name: String },
+
+    #[snafu(display("Tag '{}' already exists, merge the tags instead", name))]
+    NameAlreadyTaken { name: String },
+
+    #[snafu(display("'{}' is a synonym of another tag", name))]
+    NameIsASynonym { name: String },
+
+    #[snafu(display("Slug '{}' is not valid", slug))]
+    InvalidSlug { slug: String },
+
+    #[snafu(display("Slug '{}' is already used by another tag", slug))]
+    SlugAlreadyExists { slug: String },
+
+    #[snafu(display("A tag can't be merged into itself"))]
+    SelfMerge,
+
+    #[snafu(display("Failed in RelatedPostsRepository: {}", source))]
+    RelatedRepoFailed { source: RelatedError },
+
+    #[snafu(display("Failed in TagHierarchyRepository: {}", source))]
+    HierarchyRepoFailed { source: HierarchyError },
+
+    #[snafu(display("Failed in TagSynonymsRepository: {}", source))]
+    SynonymsRepoFailed { source: SynonymsError },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::testing, posts::PostsRepository};
+
+    async fn tagged_with(conn: &DatabaseConnection, tag: &tags::Model) -> usize {
+        PostTag::find()
+            .filter(post_tags::Column::TagUuid.eq(tag.uuid.clone()))
+            .all(conn)
+            .await
+            .unwrap()
+            .len()
+    }
+
+    #[rocket::async_test]
+    async fn merging_rolls_back_when_a_step_fails() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "writer@example.com").await;
+        PostsRepository::create(&conn, testing::new_post(user, "Ownership", &["Rust"]))
+            .await
+            .unwrap();
+        PostsRepository::create(&conn, testing::new_post(user, "Borrowing", &["Rust Lang"]))
+            .await
+            .unwrap();
+        PostsRepository::create(
+            &conn,
+            testing::new_post(user, "Lifetimes", &["Rust", "Rust Lang"]),
+        )
+        .await
+        .unwrap();
+
+        let tags = TagsRepository::find_or_create_tags(
+            &conn,
+            vec![String::from("Rust Lang"), String::from("Rust")],
+        )
+        .await
+        .unwrap();
+        let (from, into) = (&tags[0], &tags[1]);
+
+        // Keeping the merged name as a synonym is the last step before the tag is deleted.
+        testing::fail_inserts_into(&conn, "tag_synonyms").await;
+        let merged = TagsRepository::merge(
+            &conn,
+            Uuid::from_bytes(from.uuid()),
+            Uuid::from_bytes(into.uuid()),
+        )
+        .await;
+        assert!(merged.is_err());
+
+        assert_eq!(testing::count_rows(&conn, "tags").await, 2);
+        assert_eq!(testing::count_rows(&conn, "post_tags").await, 4);
+        assert_eq!(tagged_with(&conn, from).await, 2);
+        assert_eq!(tagged_with(&conn, into).await, 2);
+    }
 }
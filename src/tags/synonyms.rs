@@ -0,0 +1,186 @@
+use crate::{tags::name_key, utils::uuid::get_uuid_bytes};
+use entity::{
+    tag_synonyms::{self, Entity as TagSynonym},
+    tags::{self, Entity as Tag},
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+pub struct TagSynonymsRepository;
+
+impl TagSynonymsRepository {
+    /// Makes `alias` stand for the tag, posts tagged with it get the tag instead. The name of
+    /// another tag can't be an alias, those two tags should be merged.
+    pub async fn add(
+        conn: &DatabaseConnection,
+        tag_uuid: Uuid,
+        alias: &str,
+    ) -> Result<tag_synonyms::Model> {
+        let tag = Tag::find_by_id(tag_uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .context(TagNotFoundSnafu { uuid: tag_uuid })?;
+
+        let key = name_key(alias);
+        ensure!(!key.is_empty(), InvalidAliasSnafu { alias });
+
+        let named = Tag::find()
+            .filter(tags::Column::NormalizedName.eq(key.clone()))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+        ensure!(named.is_none(), AliasIsATagSnafu { alias: key });
+
+        let existing = TagSynonymsRepository::find_by_alias(conn, &key).await?;
+        ensure!(existing.is_none(), AliasAlreadyExistsSnafu { alias: key });
+
+        let synonym = tag_synonyms::ActiveModel {
+            alias: Set(key),
+            tag_uuid: Set(tag.uuid),
+            ..Default::default()
+        };
+
+        let result = TagSynonym::insert(synonym)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let uuid = Uuid::from_bytes(get_uuid_bytes(&result.last_insert_id));
+        TagSynonym::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .context(SynonymNotFoundSnafu { uuid })
+    }
+
+    pub async fn remove<C>(conn: &C, uuid: Uuid) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let result = TagSynonym::delete_by_id(uuid.as_bytes().to_vec())
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        ensure!(result.rows_affected > 0, SynonymNotFoundSnafu { uuid });
+
+        Ok(())
+    }
+
+    pub async fn find_by_tag(
+        conn: &DatabaseConnection,
+        tag_uuid: Uuid,
+    ) -> Result<Vec<tag_synonyms::Model>> {
+        TagSynonym::find()
+            .filter(tag_synonyms::Column::TagUuid.eq(tag_uuid.as_bytes().to_vec()))
+            .order_by_asc(tag_synonyms::Column::Alias)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// `alias` is expected to be normalized already, see `name_key`.
+    pub async fn find_by_alias<C>(conn: &C, alias: &str) -> Result<Option<tag_synonyms::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        TagSynonym::find()
+            .filter(tag_synonyms::Column::Alias.eq(alias))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// The canonical tags of the given normalized aliases, paired with the alias they stand for.
+    pub async fn resolve<C>(conn: &C, aliases: Vec<String>) -> Result<Vec<(String, tags::Model)>>
+    where
+        C: ConnectionTrait,
+    {
+        let resolved = TagSynonym::find()
+            .find_also_related(Tag)
+            .filter(tag_synonyms::Column::Alias.is_in(aliases))
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?
+            .into_iter()
+            .filter_map(|(synonym, tag)| tag.map(|tag| (synonym.alias, tag)))
+            .collect();
+
+        Ok(resolved)
+    }
+
+    /// Hands the synonyms of a tag about to be merged over to the tag it's merged into, and
+    /// keeps its name around as one more synonym so it keeps being recognized.
+    pub async fn absorb<C>(conn: &C, merged: &tags::Model, into: &tags::Model) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        TagSynonym::update_many()
+            .col_expr(
+                tag_synonyms::Column::TagUuid,
+                Expr::value(into.uuid.clone()),
+            )
+            .filter(tag_synonyms::Column::TagUuid.eq(merged.uuid.clone()))
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let alias = merged.normalized_name.clone();
+        if alias.is_empty() || alias == into.normalized_name {
+            return Ok(());
+        }
+
+        // Another tag may still go by that name, it's the one found by it then.
+        let named = Tag::find()
+            .filter(tags::Column::NormalizedName.eq(alias.clone()))
+            .filter(tags::Column::Uuid.ne(merged.uuid.clone()))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+        let existing = TagSynonymsRepository::find_by_alias(conn, &alias).await?;
+        if named.is_some() || existing.is_some() {
+            return Ok(());
+        }
+
+        let synonym = tag_synonyms::ActiveModel {
+            alias: Set(alias),
+            tag_uuid: Set(into.uuid.clone()),
+            ..Default::default()
+        };
+
+        TagSynonym::insert(synonym)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Tag Synonyms Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Tag with uuid {} not found", uuid))]
+    TagNotFound { uuid: Uuid },
+
+    #[snafu(display("Synonym with uuid {} not found", uuid))]
+    SynonymNotFound { uuid: Uuid },
+
+    #[snafu(display("Alias '{}' is not valid", alias))]
+    InvalidAlias { alias: String },
+
+    #[snafu(display("Tag '{}' already exists, merge the tags instead", alias))]
+    AliasIsATag { alias: String },
+
+    #[snafu(display("'{}' is already a synonym", alias))]
+    AliasAlreadyExists { alias: String },
+}
@@ -31,9 +31,8 @@ impl From<uploads::Model> for FileUpload {
     fn from(file_upload: uploads::Model) -> Self {
         let uuid = Uuid::from_bytes(file_upload.uuid());
 
-        let api_url = env::var("API_URL").expect("API_URL is not set");
         let urls = Urls {
-            url: format!("{}/file/{}", api_url, uuid),
+            url: file_url(&uuid),
         };
 
         // let created_by = Uuid::from_bytes(file_upload.created_by());
@@ -48,3 +47,10 @@ impl From<uploads::Model> for FileUpload {
         }
     }
 }
+
+/// Where the file is served from, see `handlers`.
+pub fn file_url(uuid: &Uuid) -> String {
+    let api_url = env::var("API_URL").expect("API_URL is not set");
+
+    format!("{}/file/{}", api_url, uuid)
+}
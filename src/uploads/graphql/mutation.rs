@@ -1,10 +1,12 @@
 use crate::{
     authorization::graphql::{Action, Resource, RoleGuard},
+    builds::coordinator::notify_change,
     graphql::context::{get_aws_from_context, get_conn_from_context, get_token_from_context},
     uploads::{
         aws::{remove_from_s3, upload_to_s3},
         graphql::FileUpload,
         repository::{UploadsRepository, UploadsRepositoryInput},
+        ContentType,
     },
 };
 use async_graphql::{Context, Object, Result, Upload, ID};
@@ -26,6 +28,8 @@ impl UploadMutation {
         let conn = get_conn_from_context(ctx).await?;
 
         let upload = file.value(ctx)?;
+        // Checked before uploading anything, a refused file never reaches S3.
+        let content_type: ContentType = upload.content_type.clone().try_into()?;
 
         let s3_key = upload_to_s3(
             aws,
@@ -39,11 +43,22 @@ impl UploadMutation {
         let input = UploadsRepositoryInput {
             user_uuid: token.uuid,
             filename: upload.filename,
-            s3_key,
-            content_type: upload.content_type.try_into()?,
+            s3_key: s3_key.clone(),
+            content_type,
         };
 
-        let upload = UploadsRepository::create(conn, input).await?;
+        // Nothing would point to the object without its row, so it doesn't stay in the bucket.
+        let upload = match UploadsRepository::create(conn, input).await {
+            Ok(upload) => upload,
+            Err(err) => {
+                if let Err(remove_err) = remove_from_s3(aws, &s3_key).await {
+                    error!("Failed to remove {} from S3: {}", s3_key, remove_err);
+                }
+
+                return Err(err.into());
+            }
+        };
+        notify_change(conn, "File uploaded").await;
 
         Ok(upload.into())
     }
@@ -58,9 +73,13 @@ impl UploadMutation {
             .await?
             .context(MissingFileSnafu { uuid })?;
 
+        // The row is only gone once the object is, a failure on S3 keeps the file around. S3
+        // doesn't mind deleting a missing object, so a row left behind can be removed again.
         remove_from_s3(aws, &file.s3_key).await?;
         UploadsRepository::remove(conn, file).await?;
 
+        notify_change(conn, "File removed").await;
+
         Ok(uuid.into())
     }
 }
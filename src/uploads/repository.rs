@@ -2,7 +2,7 @@ use super::ContentType;
 use crate::utils::uuid::get_uuid_bytes;
 use entity::uploads::{self, Entity as Upload};
 use sea_orm::entity::*;
-use sea_orm::{DatabaseConnection, DbErr};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr};
 use snafu::prelude::*;
 use uuid::Uuid;
 
@@ -17,10 +17,10 @@ pub struct UploadsRepositoryInput {
 pub struct UploadsRepository;
 
 impl UploadsRepository {
-    pub async fn create(
-        conn: &DatabaseConnection,
-        input: UploadsRepositoryInput,
-    ) -> Result<uploads::Model> {
+    pub async fn create<C>(conn: &C, input: UploadsRepositoryInput) -> Result<uploads::Model>
+    where
+        C: ConnectionTrait,
+    {
         let photo = uploads::ActiveModel {
             filename: Set(input.filename),
             content_type: Set(input.content_type.to_string()),
@@ -40,16 +40,19 @@ impl UploadsRepository {
             .context(FileNotFoundSnafu { id: last_insert_id })
     }
 
-    pub async fn remove(conn: &DatabaseConnection, upload: uploads::Model) -> Result<()> {
+    pub async fn remove<C>(conn: &C, upload: uploads::Model) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
         upload.delete(conn).await.context(QueryFailedSnafu)?;
 
         Ok(())
     }
 
-    pub async fn find_by_id(
-        conn: &DatabaseConnection,
-        uuid: Uuid,
-    ) -> Result<Option<uploads::Model>> {
+    pub async fn find_by_id<C>(conn: &C, uuid: Uuid) -> Result<Option<uploads::Model>>
+    where
+        C: ConnectionTrait,
+    {
         Upload::find_by_id(uuid.as_bytes().to_vec())
             .one(conn)
             .await
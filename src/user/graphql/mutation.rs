@@ -1,9 +1,11 @@
 use crate::{
     authorization::graphql::{Action, Resource, RoleGuard},
-    graphql::context::get_conn_from_context,
+    builds::coordinator::notify_change,
+    graphql::context::{get_conn_from_context, get_token_from_context},
     user::{
         graphql::{Role, User},
-        ChangeRoleInput as ChangeRoleRepositoryInput, UserRepository, UserRepositoryInput,
+        ChangeRoleInput as ChangeRoleRepositoryInput, LinkInput, ProfileInput, ProfilesRepository,
+        UserRepository, UserRepositoryInput,
     },
 };
 use async_graphql::*;
@@ -25,6 +27,23 @@ pub struct ChangeRoleInput {
     pub role: Role,
 }
 
+#[derive(InputObject)]
+pub struct UpdateProfileInput {
+    pub display_name: Option<String>,
+    pub handle: Option<String>,
+    /// Markdown.
+    pub bio: Option<String>,
+    /// An uploaded file.
+    pub avatar_uuid: Option<ID>,
+    pub links: Vec<UserLinkInput>,
+}
+
+#[derive(InputObject)]
+pub struct UserLinkInput {
+    pub label: String,
+    pub url: String,
+}
+
 #[derive(Default)]
 pub struct UserMutation;
 
@@ -81,6 +100,42 @@ impl UserMutation {
 
         Ok(user.into())
     }
+
+    /// Replaces the profile of the signed-in user, fields left out are cleared.
+    #[graphql(guard = "RoleGuard::authenticated()")]
+    pub async fn update_profile(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateProfileInput,
+    ) -> Result<User> {
+        let conn = get_conn_from_context(ctx).await?;
+        let token = get_token_from_context(ctx).await?.expect("Missing Token");
+
+        let avatar = match input.avatar_uuid {
+            Some(uuid) => Some(Uuid::from_str(&uuid)?),
+            None => None,
+        };
+
+        let input = ProfileInput {
+            display_name: input.display_name,
+            handle: input.handle,
+            bio: input.bio,
+            avatar,
+            links: input
+                .links
+                .into_iter()
+                .map(|link| LinkInput {
+                    label: link.label,
+                    url: link.url,
+                })
+                .collect(),
+        };
+
+        let user = ProfilesRepository::update(conn, token.uuid, input).await?;
+        notify_change(conn, "Profile updated").await;
+
+        Ok(user.into())
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -1,23 +1,75 @@
 use crate::{
     authorization::graphql::{Action, Resource, RoleGuard},
-    graphql::context::get_conn_from_context,
-    user::repository::UserRepository,
+    graphql::context::{get_conn_from_context, get_renderer_from_context},
+    uploads::{graphql::FileUpload, UploadsRepository},
+    user::{repository::UserRepository, ProfilesRepository},
 };
 use async_graphql::*;
 use entity::{
     enums::Role as UserRole,
+    user_links,
     users::{self},
 };
 use sea_orm::entity::prelude::Uuid;
 use std::{convert::From, str::FromStr};
 
 #[derive(SimpleObject, Clone)]
+#[graphql(complex)]
 pub struct User {
     pub uuid: ID,
     pub email: String,
     pub role: Role,
     pub created_at: String,
     pub updated_at: String,
+    /// Name shown on the site instead of the email.
+    pub display_name: Option<String>,
+    /// Identifies the author page, e.g. `/authors/jane-doe`.
+    pub handle: Option<String>,
+    /// Markdown source of the bio.
+    pub bio: Option<String>,
+    #[graphql(skip)]
+    pub avatar_uuid: Option<Uuid>,
+}
+
+#[ComplexObject]
+impl User {
+    async fn bio_html(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let renderer = get_renderer_from_context(ctx).await?;
+
+        Ok(self.bio.as_deref().map(|bio| renderer.render(bio)))
+    }
+
+    async fn avatar(&self, ctx: &Context<'_>) -> Result<Option<FileUpload>> {
+        let uuid = match self.avatar_uuid {
+            Some(uuid) => uuid,
+            None => return Ok(None),
+        };
+
+        let conn = get_conn_from_context(ctx).await?;
+        let upload = UploadsRepository::find_by_id(conn, uuid).await?;
+
+        Ok(upload.map(|u| u.into()))
+    }
+
+    async fn links(&self, ctx: &Context<'_>) -> Result<Vec<UserLink>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&self.uuid)?;
+
+        let links = ProfilesRepository::find_links(conn, uuid)
+            .await?
+            .into_iter()
+            .map(|link| link.into())
+            .collect();
+
+        Ok(links)
+    }
+}
+
+/// Somewhere else to find the user, like their own site or a social network.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct UserLink {
+    pub label: String,
+    pub url: String,
 }
 
 #[derive(Default)]
@@ -70,6 +122,7 @@ impl From<UserRole> for Role {
 impl From<users::Model> for User {
     fn from(user: users::Model) -> Self {
         let uuid = Uuid::from_bytes(user.uuid());
+        let avatar_uuid = user.avatar_uuid().map(Uuid::from_bytes);
 
         User {
             uuid: uuid.into(),
@@ -77,6 +130,19 @@ impl From<users::Model> for User {
             role: UserRole::from_str(&user.role).unwrap().into(),
             created_at: user.created_at.to_string(),
             updated_at: user.updated_at.to_string(),
+            display_name: user.display_name,
+            handle: user.handle,
+            bio: user.bio,
+            avatar_uuid,
+        }
+    }
+}
+
+impl From<user_links::Model> for UserLink {
+    fn from(link: user_links::Model) -> Self {
+        UserLink {
+            label: link.label,
+            url: link.url,
         }
     }
 }
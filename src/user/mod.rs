@@ -1,4 +1,6 @@
 pub mod graphql;
+mod profiles;
 mod repository;
 
+pub use profiles::{Error as ProfilesError, LinkInput, ProfileInput, ProfilesRepository};
 pub use repository::*;
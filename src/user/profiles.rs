@@ -0,0 +1,203 @@
+use crate::utils::{datetime::get_now, slug::slugify};
+use entity::{
+    uploads::Entity as Upload,
+    user_links::{self, Entity as UserLink},
+    users::{self, Entity as User},
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+/// Longest handle accepted, it ends up in the URL of the author page.
+const MAX_HANDLE_LENGTH: usize = 40;
+
+#[derive(Clone, Debug)]
+pub struct ProfileInput {
+    pub display_name: Option<String>,
+    pub handle: Option<String>,
+    /// Markdown, rendered when it's read.
+    pub bio: Option<String>,
+    pub avatar: Option<Uuid>,
+    /// Listed on the profile in the given order.
+    pub links: Vec<LinkInput>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LinkInput {
+    pub label: String,
+    pub url: String,
+}
+
+pub struct ProfilesRepository;
+
+impl ProfilesRepository {
+    /// Replaces the whole profile of a user, fields left empty are cleared.
+    pub async fn update(
+        conn: &DatabaseConnection,
+        uuid: Uuid,
+        input: ProfileInput,
+    ) -> Result<users::Model> {
+        let txn = conn.begin().await.context(QueryFailedSnafu)?;
+
+        let user = User::find_by_id(uuid.as_bytes().to_vec())
+            .one(&txn)
+            .await
+            .context(QueryFailedSnafu)?
+            .context(UserNotFoundSnafu { uuid })?;
+
+        let handle = match clean(input.handle) {
+            Some(raw) => Some(ProfilesRepository::resolve_handle(&txn, uuid, raw).await?),
+            None => None,
+        };
+
+        if let Some(avatar) = input.avatar {
+            let upload = Upload::find_by_id(avatar.as_bytes().to_vec())
+                .one(&txn)
+                .await
+                .context(QueryFailedSnafu)?;
+            ensure!(upload.is_some(), AvatarNotFoundSnafu { uuid: avatar });
+        }
+
+        let links = input
+            .links
+            .into_iter()
+            .map(|link| {
+                let label = link.label.trim().to_string();
+                let url = link.url.trim().to_string();
+                ensure!(!label.is_empty(), MissingLinkLabelSnafu { url });
+                ensure!(
+                    url.starts_with("https://") || url.starts_with("http://"),
+                    InvalidLinkSnafu { url }
+                );
+
+                Ok(LinkInput { label, url })
+            })
+            .collect::<Result<Vec<LinkInput>>>()?;
+
+        let mut user: users::ActiveModel = user.into();
+        user.display_name = Set(clean(input.display_name));
+        user.handle = Set(handle);
+        user.bio = Set(clean(input.bio));
+        user.avatar_uuid = Set(input.avatar.map(|a| a.as_bytes().to_vec()));
+        user.updated_at = Set(get_now());
+
+        let user = user.update(&txn).await.context(QueryFailedSnafu)?;
+        ProfilesRepository::set_links(&txn, uuid, links).await?;
+        txn.commit().await.context(QueryFailedSnafu)?;
+
+        Ok(user)
+    }
+
+    pub async fn find_by_handle(
+        conn: &DatabaseConnection,
+        handle: &str,
+    ) -> Result<Option<users::Model>> {
+        User::find()
+            .filter(users::Column::Handle.eq(slugify(handle)))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    pub async fn find_links(
+        conn: &DatabaseConnection,
+        uuid: Uuid,
+    ) -> Result<Vec<user_links::Model>> {
+        UserLink::find()
+            .filter(user_links::Column::UserUuid.eq(uuid.as_bytes().to_vec()))
+            .order_by_asc(user_links::Column::Position)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    async fn set_links<C>(conn: &C, uuid: Uuid, links: Vec<LinkInput>) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        UserLink::delete_many()
+            .filter(user_links::Column::UserUuid.eq(uuid.as_bytes().to_vec()))
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        if links.is_empty() {
+            return Ok(());
+        }
+
+        let links: Vec<user_links::ActiveModel> = links
+            .into_iter()
+            .enumerate()
+            .map(|(position, link)| user_links::ActiveModel {
+                user_uuid: Set(uuid.as_bytes().to_vec()),
+                label: Set(link.label),
+                url: Set(link.url),
+                position: Set(position as i32),
+                ..Default::default()
+            })
+            .collect();
+
+        UserLink::insert_many(links)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    /// Handles follow the slug rules and, unlike slugs, are never suffixed: a taken handle is
+    /// refused so nobody ends up with one they didn't pick.
+    async fn resolve_handle<C>(conn: &C, uuid: Uuid, raw: String) -> Result<String>
+    where
+        C: ConnectionTrait,
+    {
+        let handle = slugify(&raw);
+        ensure!(
+            !handle.is_empty() && handle.chars().count() <= MAX_HANDLE_LENGTH,
+            InvalidHandleSnafu { handle: raw }
+        );
+
+        let existing = User::find()
+            .filter(users::Column::Handle.eq(handle.clone()))
+            .filter(users::Column::Uuid.ne(uuid.as_bytes().to_vec()))
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+        ensure!(existing.is_none(), HandleAlreadyTakenSnafu { handle });
+
+        Ok(handle)
+    }
+}
+
+fn clean(text: Option<String>) -> Option<String> {
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Profile Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("User not found with {uuid}"))]
+    UserNotFound { uuid: Uuid },
+
+    #[snafu(display("Handle '{}' is not valid", handle))]
+    InvalidHandle { handle: String },
+
+    #[snafu(display("Handle '{}' is already taken", handle))]
+    HandleAlreadyTaken { handle: String },
+
+    #[snafu(display("Avatar does not exist with uuid {}", uuid))]
+    AvatarNotFound { uuid: Uuid },
+
+    #[snafu(display("Link to {} needs a label", url))]
+    MissingLinkLabel { url: String },
+
+    #[snafu(display("Link '{}' is not an http(s) URL", url))]
+    InvalidLink { url: String },
+}
@@ -0,0 +1,32 @@
+use similar::{ChangeTag, TextDiff};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LineChange {
+    Added,
+    Removed,
+    Unchanged,
+}
+
+/// Line based diff between two texts. Myers' algorithm runs in linear space, so long revisions
+/// don't need a table of every pair of lines, and past the deadline it settles for a coarser diff.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<(LineChange, &'a str)> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let diff = TextDiff::configure()
+        .timeout(Duration::from_secs(1))
+        .diff_slices(&old, &new);
+
+    diff.iter_all_changes()
+        .map(|change| {
+            let kind = match change.tag() {
+                ChangeTag::Insert => LineChange::Added,
+                ChangeTag::Delete => LineChange::Removed,
+                ChangeTag::Equal => LineChange::Unchanged,
+            };
+
+            (kind, change.value())
+        })
+        .collect()
+}
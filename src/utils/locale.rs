@@ -0,0 +1,44 @@
+use std::env;
+
+/// The same page in another language, as linked with `hreflang`.
+#[derive(Debug, Clone)]
+pub struct Alternate {
+    pub locale: String,
+    pub url: String,
+}
+
+/// Locale of the posts created without one, `SITE_LOCALE` or English.
+pub fn default_locale() -> String {
+    env::var("SITE_LOCALE")
+        .ok()
+        .and_then(|locale| normalize_locale(&locale))
+        .unwrap_or_else(|| String::from("en"))
+}
+
+/// Brings a language tag to the form used in `hreflang`, e.g. `"es_mx"` becomes `"es-MX"`.
+/// Only a language with an optional region is accepted.
+pub fn normalize_locale(locale: &str) -> Option<String> {
+    let mut parts = locale.trim().split(['-', '_']);
+
+    let language = parts.next()?;
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let region = parts.next();
+    if parts.next().is_some() {
+        return None;
+    }
+
+    match region {
+        None => Some(language.to_ascii_lowercase()),
+        Some(region) if region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Some(format!(
+                "{}-{}",
+                language.to_ascii_lowercase(),
+                region.to_ascii_uppercase()
+            ))
+        }
+        Some(_) => None,
+    }
+}
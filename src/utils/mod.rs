@@ -1,4 +1,10 @@
 pub mod cors;
 pub mod datetime;
+pub mod diff;
+pub mod locale;
+pub mod pagination;
+pub mod site;
+pub mod slug;
 pub mod uuid;
 pub mod vec;
+pub mod xml;
@@ -0,0 +1,115 @@
+use async_graphql::connection::CursorType;
+use data_encoding::BASE64URL_NOPAD;
+use snafu::prelude::*;
+use std::str::{self, FromStr, Utf8Error};
+use uuid::{Error as UuidError, Uuid};
+
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Opaque position of a row inside a sorted list. It holds the value of the sorted column and the
+/// uuid of the row, so rows sharing the same value still get a stable order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub key: String,
+    pub uuid: Uuid,
+}
+
+impl Cursor {
+    pub fn new(key: impl ToString, uuid: Uuid) -> Self {
+        Cursor {
+            key: key.to_string(),
+            uuid,
+        }
+    }
+}
+
+impl CursorType for Cursor {
+    type Error = Error;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        let bytes = BASE64URL_NOPAD
+            .decode(s.as_bytes())
+            .map_err(|_| Error::MalformedCursor)?;
+        let decoded = str::from_utf8(&bytes).context(InvalidEncodingSnafu)?;
+
+        let (key, uuid) = decoded.rsplit_once('|').context(MalformedCursorSnafu)?;
+        let uuid = Uuid::from_str(uuid).context(InvalidUuidSnafu)?;
+
+        Ok(Cursor::new(key, uuid))
+    }
+
+    fn encode_cursor(&self) -> String {
+        let raw = format!("{}|{}", self.key, self.uuid);
+
+        BASE64URL_NOPAD.encode(raw.as_bytes())
+    }
+}
+
+/// Relay style arguments, `first`/`after` move forward and `last`/`before` move backwards.
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    pub after: Option<Cursor>,
+    pub before: Option<Cursor>,
+    pub first: Option<usize>,
+    pub last: Option<usize>,
+}
+
+impl Page {
+    pub fn is_backward(&self) -> bool {
+        self.first.is_none() && self.last.is_some()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.first
+            .or(self.last)
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .min(MAX_PAGE_SIZE)
+    }
+}
+
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
+}
+
+impl<T> Paginated<T> {
+    /// Builds the page out of rows queried with one extra item, which tells if there is more
+    /// data in the direction the page is moving to. Backward rows come in reverse order.
+    pub fn from_rows(mut rows: Vec<T>, page: &Page) -> Self {
+        let limit = page.limit();
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+
+        if page.is_backward() {
+            rows.reverse();
+
+            Paginated {
+                items: rows,
+                has_previous_page: has_more,
+                has_next_page: page.before.is_some(),
+            }
+        } else {
+            Paginated {
+                items: rows,
+                has_previous_page: page.after.is_some(),
+                has_next_page: has_more,
+            }
+        }
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Cursor is malformed"))]
+    MalformedCursor,
+
+    #[snafu(display("Cursor has an invalid encoding: {}", source))]
+    InvalidEncoding { source: Utf8Error },
+
+    #[snafu(display("Cursor has an invalid uuid: {}", source))]
+    InvalidUuid { source: UuidError },
+}
@@ -0,0 +1,86 @@
+use super::locale::default_locale;
+use std::env;
+
+/// Details of the public site built from this API, used anywhere an absolute URL to it is needed.
+#[derive(Debug, Clone)]
+pub struct SiteConfig {
+    pub url: String,
+    pub api_url: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    /// Language of the site as a whole, posts can be in any other.
+    pub locale: String,
+    pub posts_path: String,
+    pub tags_path: String,
+    pub authors_path: String,
+}
+
+impl SiteConfig {
+    pub fn from_env() -> Self {
+        SiteConfig {
+            url: trim_url(env::var("SITE_URL").unwrap_or_else(|_| String::from(""))),
+            api_url: trim_url(env::var("API_URL").expect("API_URL is not set")),
+            title: env::var("SITE_TITLE").unwrap_or_else(|_| String::from("Blog")),
+            description: env::var("SITE_DESCRIPTION").unwrap_or_else(|_| String::from("")),
+            author: env::var("SITE_AUTHOR").unwrap_or_else(|_| String::from("")),
+            locale: default_locale(),
+            posts_path: trim_url(env::var("SITE_POSTS_PATH").unwrap_or_else(|_| String::from(""))),
+            tags_path: trim_url(
+                env::var("SITE_TAGS_PATH").unwrap_or_else(|_| String::from("/tags")),
+            ),
+            authors_path: trim_url(
+                env::var("SITE_AUTHORS_PATH").unwrap_or_else(|_| String::from("/authors")),
+            ),
+        }
+    }
+
+    pub fn post_url(&self, slug: &str) -> String {
+        format!("{}{}/{}", self.url, self.posts_path, slug)
+    }
+
+    pub fn tag_url(&self, tag: &str) -> String {
+        format!(
+            "{}{}/{}",
+            self.url,
+            self.tags_path,
+            encode_path_segment(tag)
+        )
+    }
+
+    pub fn author_url(&self, author: &str) -> String {
+        format!(
+            "{}{}/{}",
+            self.url,
+            self.authors_path,
+            encode_path_segment(author)
+        )
+    }
+
+    /// Any other path on the public site, like the sitemaps it serves from this API.
+    pub fn site_url(&self, path: &str) -> String {
+        format!("{}{}", self.url, path)
+    }
+
+    pub fn api_url(&self, path: &str) -> String {
+        format!("{}{}", self.api_url, path)
+    }
+}
+
+fn trim_url(url: String) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+/// Percent encodes anything outside of the unreserved characters, so names with spaces or
+/// symbols can be part of a path.
+pub fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,16 @@
+/// Turns any text into a URL-safe slug, e.g. `"Hello, World!"` becomes `"hello-world"`.
+pub fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// Appends the collision suffix to a slug, the first duplicate becomes `slug-2` and so on.
+pub fn with_suffix(slug: &str, suffix: usize) -> String {
+    match suffix {
+        0 | 1 => slug.to_string(),
+        n => format!("{}-{}", slug, n),
+    }
+}
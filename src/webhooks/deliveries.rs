@@ -0,0 +1,241 @@
+use crate::utils::{datetime::get_now, uuid::get_uuid_bytes};
+use chrono::{DateTime, Duration, Utc};
+use entity::{
+    enums::{DeliveryStatus, WebhookEvent},
+    webhook_deliveries::{self, Entity as WebhookDelivery},
+    webhooks,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use snafu::prelude::*;
+use uuid::Uuid;
+
+/// After this many failed attempts a delivery is given up on.
+pub const MAX_ATTEMPTS: i32 = 6;
+
+/// Wait before the first retry, it doubles after every failed attempt.
+pub const BASE_BACKOFF_SECS: i64 = 30;
+
+pub struct WebhookDeliveriesRepository;
+
+impl WebhookDeliveriesRepository {
+    pub async fn enqueue<C>(
+        conn: &C,
+        webhook: &webhooks::Model,
+        event: &WebhookEvent,
+        payload: &str,
+    ) -> Result<webhook_deliveries::Model>
+    where
+        C: ConnectionTrait,
+    {
+        let delivery = webhook_deliveries::ActiveModel {
+            webhook_uuid: Set(webhook.uuid.clone()),
+            event: Set(event.to_string()),
+            payload: Set(payload.to_string()),
+            ..Default::default()
+        };
+
+        let result = WebhookDelivery::insert(delivery)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let uuid = Uuid::from_bytes(get_uuid_bytes(&result.last_insert_id));
+        WebhookDeliveriesRepository::find_by_id(conn, uuid)
+            .await?
+            .context(DeliveryNotFoundSnafu { uuid })
+    }
+
+    pub async fn find_by_id<C>(conn: &C, uuid: Uuid) -> Result<Option<webhook_deliveries::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        WebhookDelivery::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// The delivery log of a webhook, newest first.
+    pub async fn find_by_webhook(
+        conn: &DatabaseConnection,
+        webhook_uuid: Uuid,
+        limit: u64,
+    ) -> Result<Vec<webhook_deliveries::Model>> {
+        WebhookDelivery::find()
+            .filter(webhook_deliveries::Column::WebhookUuid.eq(webhook_uuid.as_bytes().to_vec()))
+            .order_by_desc(webhook_deliveries::Column::CreatedAt)
+            .limit(limit)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Pending deliveries whose next attempt is due, along with the webhook to send them to.
+    /// The ones of an inactive webhook wait until it's active again.
+    pub async fn find_due(
+        conn: &DatabaseConnection,
+        now: DateTime<Utc>,
+        limit: u64,
+    ) -> Result<Vec<(webhook_deliveries::Model, Option<webhooks::Model>)>> {
+        WebhookDelivery::find()
+            .find_also_related(webhooks::Entity)
+            .filter(webhook_deliveries::Column::Status.eq(DeliveryStatus::Pending.to_string()))
+            .filter(webhook_deliveries::Column::NextAttemptAt.lte(now))
+            .filter(
+                Condition::any()
+                    .add(webhooks::Column::Active.eq(true))
+                    .add(webhooks::Column::Uuid.is_null()),
+            )
+            .order_by_asc(webhook_deliveries::Column::NextAttemptAt)
+            .limit(limit)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    pub async fn mark_delivered(
+        conn: &DatabaseConnection,
+        delivery: webhook_deliveries::Model,
+        response_status: i32,
+    ) -> Result<webhook_deliveries::Model> {
+        let attempts = delivery.attempts + 1;
+
+        let mut delivery: webhook_deliveries::ActiveModel = delivery.into();
+        delivery.status = Set(DeliveryStatus::Delivered.to_string());
+        delivery.attempts = Set(attempts);
+        delivery.response_status = Set(Some(response_status));
+        delivery.error = Set(None);
+        delivery.next_attempt_at = Set(None);
+        delivery.updated_at = Set(get_now());
+
+        delivery.update(conn).await.context(QueryFailedSnafu)
+    }
+
+    /// Gives up on the delivery without attempting it, e.g. once its webhook is gone.
+    pub async fn mark_failed(
+        conn: &DatabaseConnection,
+        delivery: webhook_deliveries::Model,
+        error: String,
+    ) -> Result<webhook_deliveries::Model> {
+        let mut delivery: webhook_deliveries::ActiveModel = delivery.into();
+        delivery.status = Set(DeliveryStatus::Failed.to_string());
+        delivery.error = Set(Some(error));
+        delivery.next_attempt_at = Set(None);
+        delivery.updated_at = Set(get_now());
+
+        delivery.update(conn).await.context(QueryFailedSnafu)
+    }
+
+    /// Schedules the next attempt with exponential backoff, or gives up once the delivery
+    /// reached `MAX_ATTEMPTS`.
+    pub async fn mark_attempt_failed(
+        conn: &DatabaseConnection,
+        delivery: webhook_deliveries::Model,
+        response_status: Option<i32>,
+        error: String,
+    ) -> Result<webhook_deliveries::Model> {
+        let now = get_now();
+        let attempts = delivery.attempts + 1;
+
+        let mut delivery: webhook_deliveries::ActiveModel = delivery.into();
+        delivery.attempts = Set(attempts);
+        delivery.response_status = Set(response_status);
+        delivery.error = Set(Some(error));
+        delivery.updated_at = Set(now);
+
+        if attempts >= MAX_ATTEMPTS {
+            delivery.status = Set(DeliveryStatus::Failed.to_string());
+            delivery.next_attempt_at = Set(None);
+        } else {
+            delivery.next_attempt_at = Set(Some(now + backoff(attempts)));
+        }
+
+        delivery.update(conn).await.context(QueryFailedSnafu)
+    }
+}
+
+fn backoff(attempts: i32) -> Duration {
+    Duration::seconds(BASE_BACKOFF_SECS << (attempts - 1).clamp(0, 16))
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Webhook deliveries Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Webhook delivery does not exist with uuid {}", uuid))]
+    DeliveryNotFound { uuid: Uuid },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::testing,
+        webhooks::{NewWebhookInput, WebhooksRepository},
+    };
+
+    #[test]
+    fn backoff_doubles_after_every_attempt() {
+        assert_eq!(backoff(1), Duration::seconds(30));
+        assert_eq!(backoff(2), Duration::seconds(60));
+        assert_eq!(backoff(3), Duration::seconds(120));
+        assert_eq!(backoff(5), Duration::seconds(480));
+    }
+
+    #[rocket::async_test]
+    async fn schedules_retries_until_giving_up() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "admin@example.com").await;
+        let input = NewWebhookInput {
+            url: String::from("https://example.com/hook"),
+            secret: None,
+            events: vec![WebhookEvent::PostCreated],
+            created_by: user,
+        };
+        let webhook = WebhooksRepository::create(&conn, input).await.unwrap();
+
+        let mut delivery =
+            WebhookDeliveriesRepository::enqueue(&conn, &webhook, &WebhookEvent::PostCreated, "{}")
+                .await
+                .unwrap();
+
+        for attempt in 1..MAX_ATTEMPTS {
+            let before = get_now();
+            delivery = WebhookDeliveriesRepository::mark_attempt_failed(
+                &conn,
+                delivery,
+                Some(500),
+                String::from("Webhook answered with 500"),
+            )
+            .await
+            .unwrap();
+            let after = get_now();
+
+            let next_attempt_at = delivery.next_attempt_at.unwrap();
+            assert_eq!(delivery.status, DeliveryStatus::Pending.to_string());
+            assert_eq!(delivery.attempts, attempt);
+            assert!(next_attempt_at >= before + backoff(attempt));
+            assert!(next_attempt_at <= after + backoff(attempt));
+        }
+
+        let delivery = WebhookDeliveriesRepository::mark_attempt_failed(
+            &conn,
+            delivery,
+            None,
+            String::from("Connection refused"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(delivery.status, DeliveryStatus::Failed.to_string());
+        assert_eq!(delivery.attempts, MAX_ATTEMPTS);
+        assert_eq!(delivery.next_attempt_at, None);
+        assert_eq!(delivery.error.as_deref(), Some("Connection refused"));
+    }
+}
@@ -0,0 +1,351 @@
+use crate::{
+    db::Db,
+    utils::datetime::get_now,
+    webhooks::{sign, WebhookDeliveriesRepository, SIGNATURE_HEADER},
+};
+use entity::{webhook_deliveries, webhooks};
+use reqwest::Client;
+use rocket::{
+    fairing::AdHoc,
+    tokio::{self, time},
+};
+use sea_orm::DatabaseConnection;
+use sea_orm_rocket::Database;
+use std::{env, time::Duration};
+use uuid::Uuid;
+
+/// How many deliveries are sent on every tick.
+const BATCH_SIZE: u64 = 50;
+
+/// Fairing that starts the background task sending the queued webhook deliveries.
+pub fn init_dispatcher() -> AdHoc {
+    AdHoc::on_liftoff("Webhooks Dispatcher", |rocket| {
+        Box::pin(async move {
+            let conn = match Db::fetch(rocket) {
+                Some(db) => db.conn.clone(),
+                None => {
+                    error!("Database is not available, webhooks won't be delivered");
+                    return;
+                }
+            };
+
+            let interval = env::var("WEBHOOKS_INTERVAL_SECS")
+                .ok()
+                .and_then(|secs| secs.parse::<u64>().ok())
+                .unwrap_or(10);
+
+            let timeout = env::var("WEBHOOKS_TIMEOUT_SECS")
+                .ok()
+                .and_then(|secs| secs.parse::<u64>().ok())
+                .unwrap_or(10);
+
+            let client = match Client::builder()
+                .timeout(Duration::from_secs(timeout))
+                .build()
+            {
+                Ok(client) => client,
+                Err(err) => {
+                    error!("Failed to build the webhooks client: {}", err);
+                    return;
+                }
+            };
+
+            tokio::spawn(async move {
+                let mut interval = time::interval(Duration::from_secs(interval));
+
+                loop {
+                    interval.tick().await;
+
+                    dispatch_due(&conn, &client).await;
+                }
+            });
+        })
+    })
+}
+
+/// Sends the deliveries that are due, the ones whose webhook is gone are given up on.
+async fn dispatch_due(conn: &DatabaseConnection, client: &Client) {
+    let due = match WebhookDeliveriesRepository::find_due(conn, get_now(), BATCH_SIZE).await {
+        Ok(due) => due,
+        Err(err) => {
+            error!("Failed to load the webhook deliveries: {}", err);
+            return;
+        }
+    };
+
+    for (delivery, webhook) in due {
+        match webhook {
+            Some(webhook) => deliver(conn, client, delivery, &webhook).await,
+            None => abandon(conn, delivery).await,
+        }
+    }
+}
+
+async fn abandon(conn: &DatabaseConnection, delivery: webhook_deliveries::Model) {
+    let uuid = Uuid::from_bytes(delivery.uuid());
+    let error = String::from("Webhook does not exist anymore");
+
+    if let Err(err) = WebhookDeliveriesRepository::mark_failed(conn, delivery, error).await {
+        error!("Failed to save the webhook delivery {}: {}", uuid, err);
+    }
+}
+
+async fn deliver(
+    conn: &DatabaseConnection,
+    client: &Client,
+    delivery: webhook_deliveries::Model,
+    webhook: &webhooks::Model,
+) {
+    let uuid = Uuid::from_bytes(delivery.uuid());
+
+    let response = client
+        .post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Event", &delivery.event)
+        .header("X-Webhook-Delivery", uuid.to_string())
+        .header(SIGNATURE_HEADER, sign(&webhook.secret, &delivery.payload))
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    let result = match response {
+        Ok(response) if response.status().is_success() => {
+            let status = response.status().as_u16() as i32;
+            WebhookDeliveriesRepository::mark_delivered(conn, delivery, status).await
+        }
+        Ok(response) => {
+            let status = response.status();
+            let error = format!("Webhook answered with {}", status);
+            WebhookDeliveriesRepository::mark_attempt_failed(
+                conn,
+                delivery,
+                Some(status.as_u16() as i32),
+                error,
+            )
+            .await
+        }
+        Err(err) => {
+            WebhookDeliveriesRepository::mark_attempt_failed(conn, delivery, None, err.to_string())
+                .await
+        }
+    };
+
+    match result {
+        Ok(delivery) => info!(
+            "Webhook delivery {} to {}: {}",
+            uuid, webhook.url, delivery.status
+        ),
+        Err(err) => error!("Failed to save the webhook delivery {}: {}", uuid, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::testing,
+        webhooks::{NewWebhookInput, UpdateWebhookInput, WebhooksRepository},
+    };
+    use entity::enums::{DeliveryStatus, WebhookEvent};
+    use rocket::tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        sync::mpsc,
+    };
+    use sea_orm::{ConnectionTrait, Statement};
+
+    /// Local stand-in for the receiving server, it answers each request with the next status and
+    /// hands the raw request over to the test.
+    async fn serve(statuses: Vec<u16>) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let request = read_request(&mut socket).await;
+                sender.send(request).unwrap();
+
+                let response = format!(
+                    "HTTP/1.1 {} Stand-in\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, receiver)
+    }
+
+    async fn read_request(socket: &mut TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+
+        loop {
+            let read = socket.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..read]);
+
+            let text = String::from_utf8_lossy(&request).to_lowercase();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .and_then(|length| length.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+
+                if request.len() >= end + 4 + length {
+                    return text;
+                }
+            }
+
+            if read == 0 {
+                return text;
+            }
+        }
+    }
+
+    #[rocket::async_test]
+    async fn retries_a_delivery_after_a_server_error() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "admin@example.com").await;
+        let (url, mut requests) = serve(vec![500, 200]).await;
+
+        let input = NewWebhookInput {
+            url,
+            secret: Some(String::from("whsec_test")),
+            events: vec![WebhookEvent::PostCreated],
+            created_by: user,
+        };
+        let webhook = WebhooksRepository::create(&conn, input).await.unwrap();
+        let payload = r#"{"event":"PostCreated"}"#;
+        let delivery = WebhookDeliveriesRepository::enqueue(
+            &conn,
+            &webhook,
+            &WebhookEvent::PostCreated,
+            payload,
+        )
+        .await
+        .unwrap();
+        let uuid = Uuid::from_bytes(delivery.uuid());
+        let client = Client::builder().no_proxy().build().unwrap();
+
+        deliver(&conn, &client, delivery, &webhook).await;
+
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with("post /hook "));
+        assert!(request.contains(
+            "x-webhook-signature: sha256=4906e23eed3ada9d2f68eff4dbc7774d9e87f12d97be726c56fe1bbf45d0b6c0"
+        ));
+        assert!(request.contains("x-webhook-event: postcreated"));
+        assert!(request.ends_with(&payload.to_lowercase()));
+
+        let failed = WebhookDeliveriesRepository::find_by_id(&conn, uuid)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(failed.status, DeliveryStatus::Pending.to_string());
+        assert_eq!(failed.attempts, 1);
+        assert_eq!(failed.response_status, Some(500));
+
+        // It waits for the backoff before being sent again.
+        let due = WebhookDeliveriesRepository::find_due(&conn, get_now(), BATCH_SIZE)
+            .await
+            .unwrap();
+        assert!(due.is_empty());
+
+        let retry_at = failed.next_attempt_at.unwrap() + chrono::Duration::seconds(1);
+        let due = WebhookDeliveriesRepository::find_due(&conn, retry_at, BATCH_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(due.len(), 1);
+
+        let (delivery, webhook) = due.into_iter().next().unwrap();
+        deliver(&conn, &client, delivery, &webhook.unwrap()).await;
+        requests.recv().await.unwrap();
+
+        let delivered = WebhookDeliveriesRepository::find_by_id(&conn, uuid)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivered.status, DeliveryStatus::Delivered.to_string());
+        assert_eq!(delivered.attempts, 2);
+        assert_eq!(delivered.response_status, Some(200));
+        assert_eq!(delivered.error, None);
+        assert_eq!(delivered.next_attempt_at, None);
+    }
+
+    #[rocket::async_test]
+    async fn holds_the_deliveries_of_an_inactive_webhook() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "admin@example.com").await;
+        let input = NewWebhookInput {
+            url: String::from("https://example.com/hook"),
+            secret: None,
+            events: vec![WebhookEvent::PostCreated],
+            created_by: user,
+        };
+        let webhook = WebhooksRepository::create(&conn, input).await.unwrap();
+        WebhookDeliveriesRepository::enqueue(&conn, &webhook, &WebhookEvent::PostCreated, "{}")
+            .await
+            .unwrap();
+
+        for active in [false, true] {
+            let input = UpdateWebhookInput {
+                uuid: Uuid::from_bytes(webhook.uuid()),
+                url: webhook.url.clone(),
+                events: vec![WebhookEvent::PostCreated],
+                active,
+            };
+            WebhooksRepository::update(&conn, input).await.unwrap();
+
+            let due = WebhookDeliveriesRepository::find_due(&conn, get_now(), BATCH_SIZE)
+                .await
+                .unwrap();
+            assert_eq!(due.len(), active as usize);
+        }
+    }
+
+    #[rocket::async_test]
+    async fn gives_up_on_deliveries_without_a_webhook() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "admin@example.com").await;
+        let input = NewWebhookInput {
+            url: String::from("https://example.com/hook"),
+            secret: None,
+            events: vec![WebhookEvent::PostCreated],
+            created_by: user,
+        };
+        let webhook = WebhooksRepository::create(&conn, input).await.unwrap();
+        let delivery =
+            WebhookDeliveriesRepository::enqueue(&conn, &webhook, &WebhookEvent::PostCreated, "{}")
+                .await
+                .unwrap();
+
+        // The cascade would take the delivery along, it's left behind on purpose.
+        for sql in [
+            "PRAGMA foreign_keys = OFF",
+            "DELETE FROM webhooks",
+            "PRAGMA foreign_keys = ON",
+        ] {
+            conn.execute(Statement::from_string(
+                conn.get_database_backend(),
+                sql.to_owned(),
+            ))
+            .await
+            .unwrap();
+        }
+
+        let client = Client::builder().no_proxy().build().unwrap();
+        dispatch_due(&conn, &client).await;
+
+        let abandoned =
+            WebhookDeliveriesRepository::find_by_id(&conn, Uuid::from_bytes(delivery.uuid()))
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(abandoned.status, DeliveryStatus::Failed.to_string());
+        assert_eq!(abandoned.attempts, 0);
+        assert_eq!(abandoned.next_attempt_at, None);
+    }
+}
@@ -0,0 +1,147 @@
+mod mutation;
+mod query;
+
+pub use mutation::*;
+pub use query::*;
+
+use crate::webhooks::split_events;
+use async_graphql::{Enum, SimpleObject, ID};
+use chrono::{DateTime, Utc};
+use entity::{
+    enums::{DeliveryStatus as DBDeliveryStatus, WebhookEvent as DBWebhookEvent},
+    webhook_deliveries, webhooks,
+};
+use snafu::prelude::*;
+use std::{convert::From, str::FromStr};
+use strum::ParseError;
+use uuid::Uuid;
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct Webhook {
+    pub uuid: ID,
+    pub url: String,
+    /// Key of the `X-Webhook-Signature` HMAC, receivers use it to verify the deliveries.
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<webhooks::Model> for Webhook {
+    fn from(webhook: webhooks::Model) -> Self {
+        let uuid = Uuid::from_bytes(webhook.uuid());
+
+        Webhook {
+            uuid: uuid.into(),
+            url: webhook.url,
+            secret: webhook.secret,
+            events: split_events(&webhook.events)
+                .into_iter()
+                .map(WebhookEvent::from)
+                .collect(),
+            active: webhook.active,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+/// One event sent (or about to be sent) to a webhook.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct WebhookDelivery {
+    pub uuid: ID,
+    pub webhook_uuid: ID,
+    pub event: WebhookEvent,
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<webhook_deliveries::Model> for WebhookDelivery {
+    type Error = Error;
+
+    fn try_from(delivery: webhook_deliveries::Model) -> Result<Self, Self::Error> {
+        let uuid = Uuid::from_bytes(delivery.uuid());
+        let webhook_uuid = Uuid::from_bytes(delivery.webhook_uuid());
+        let event =
+            DBWebhookEvent::from_str(&delivery.event).context(InvalidEventSnafu { uuid })?;
+        let status =
+            DBDeliveryStatus::from_str(&delivery.status).context(InvalidStatusSnafu { uuid })?;
+
+        Ok(WebhookDelivery {
+            uuid: uuid.into(),
+            webhook_uuid: webhook_uuid.into(),
+            event: event.into(),
+            payload: delivery.payload,
+            status: status.into(),
+            attempts: delivery.attempts,
+            response_status: delivery.response_status,
+            error: delivery.error,
+            next_attempt_at: delivery.next_attempt_at,
+            created_at: delivery.created_at,
+            updated_at: delivery.updated_at,
+        })
+    }
+}
+
+#[derive(Enum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WebhookEvent {
+    PostCreated,
+    PostUpdated,
+    PostStatusChanged,
+    PostDeleted,
+}
+
+impl From<WebhookEvent> for DBWebhookEvent {
+    fn from(event: WebhookEvent) -> Self {
+        match event {
+            WebhookEvent::PostCreated => DBWebhookEvent::PostCreated,
+            WebhookEvent::PostUpdated => DBWebhookEvent::PostUpdated,
+            WebhookEvent::PostStatusChanged => DBWebhookEvent::PostStatusChanged,
+            WebhookEvent::PostDeleted => DBWebhookEvent::PostDeleted,
+        }
+    }
+}
+
+impl From<DBWebhookEvent> for WebhookEvent {
+    fn from(event: DBWebhookEvent) -> Self {
+        match event {
+            DBWebhookEvent::PostCreated => WebhookEvent::PostCreated,
+            DBWebhookEvent::PostUpdated => WebhookEvent::PostUpdated,
+            DBWebhookEvent::PostStatusChanged => WebhookEvent::PostStatusChanged,
+            DBWebhookEvent::PostDeleted => WebhookEvent::PostDeleted,
+        }
+    }
+}
+
+#[derive(Enum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl From<DBDeliveryStatus> for DeliveryStatus {
+    fn from(status: DBDeliveryStatus) -> Self {
+        match status {
+            DBDeliveryStatus::Pending => DeliveryStatus::Pending,
+            DBDeliveryStatus::Delivered => DeliveryStatus::Delivered,
+            DBDeliveryStatus::Failed => DeliveryStatus::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Event: {} is not valid for Delivery: {}", source, uuid))]
+    InvalidEvent { source: ParseError, uuid: Uuid },
+
+    #[snafu(display("Status: {} is not valid for Delivery: {}", source, uuid))]
+    InvalidStatus { source: ParseError, uuid: Uuid },
+}
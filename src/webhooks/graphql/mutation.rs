@@ -0,0 +1,83 @@
+use super::{Webhook, WebhookEvent};
+use crate::{
+    authorization::graphql::{Action, Resource, RoleGuard},
+    graphql::context::{get_conn_from_context, get_token_from_context},
+    webhooks::{
+        NewWebhookInput as NewWebhookRepoInput, UpdateWebhookInput as UpdateWebhookRepoInput,
+        WebhooksRepository,
+    },
+};
+use async_graphql::{Context, InputObject, Object, Result, ID};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(InputObject)]
+pub struct NewWebhookInput {
+    /// Where the deliveries are sent, only http(s) URLs are accepted.
+    pub url: String,
+    /// Generated when left empty.
+    pub secret: Option<String>,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(InputObject)]
+pub struct UpdateWebhookInput {
+    pub uuid: ID,
+    /// Where the deliveries are sent, only http(s) URLs are accepted.
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub active: bool,
+}
+
+#[derive(Default)]
+pub struct WebhooksMutation;
+
+#[Object]
+impl WebhooksMutation {
+    #[graphql(guard = "RoleGuard::new(Resource::Webhook, Action::Write)")]
+    pub async fn new_webhook(&self, ctx: &Context<'_>, input: NewWebhookInput) -> Result<Webhook> {
+        let conn = get_conn_from_context(ctx).await?;
+        let token = get_token_from_context(ctx).await?.expect("Missing Token");
+
+        let input = NewWebhookRepoInput {
+            url: input.url,
+            secret: input.secret,
+            events: input.events.into_iter().map(|e| e.into()).collect(),
+            created_by: token.uuid,
+        };
+
+        let webhook = WebhooksRepository::create(conn, input).await?;
+
+        Ok(webhook.into())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Webhook, Action::Write)")]
+    pub async fn update_webhook(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateWebhookInput,
+    ) -> Result<Webhook> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let input = UpdateWebhookRepoInput {
+            uuid: Uuid::from_str(&input.uuid)?,
+            url: input.url,
+            events: input.events.into_iter().map(|e| e.into()).collect(),
+            active: input.active,
+        };
+
+        let webhook = WebhooksRepository::update(conn, input).await?;
+
+        Ok(webhook.into())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Webhook, Action::Write)")]
+    pub async fn delete_webhook(&self, ctx: &Context<'_>, uuid: String) -> Result<ID> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&uuid)?;
+
+        WebhooksRepository::delete(conn, uuid).await?;
+
+        Ok(uuid.into())
+    }
+}
@@ -0,0 +1,62 @@
+use super::{Webhook, WebhookDelivery};
+use crate::{
+    authorization::graphql::{Action, Resource, RoleGuard},
+    graphql::context::get_conn_from_context,
+    webhooks::{WebhookDeliveriesRepository, WebhooksRepository},
+};
+use async_graphql::{Context, Object, Result};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Deliveries listed when no limit is given.
+const DEFAULT_DELIVERIES: u64 = 50;
+
+#[derive(Default)]
+pub struct WebhooksQuery;
+
+#[Object]
+impl WebhooksQuery {
+    #[graphql(guard = "RoleGuard::new(Resource::Webhook, Action::Read)")]
+    pub async fn webhook(&self, ctx: &Context<'_>, uuid: String) -> Result<Option<Webhook>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let uuid = Uuid::from_str(&uuid)?;
+
+        let webhook = WebhooksRepository::find_by_id(conn, uuid).await?;
+
+        Ok(webhook.map(Webhook::from))
+    }
+
+    #[graphql(guard = "RoleGuard::new(Resource::Webhook, Action::Read)")]
+    pub async fn webhooks(&self, ctx: &Context<'_>) -> Result<Vec<Webhook>> {
+        let conn = get_conn_from_context(ctx).await?;
+
+        let webhooks = WebhooksRepository::find_all(conn)
+            .await?
+            .into_iter()
+            .map(Webhook::from)
+            .collect();
+
+        Ok(webhooks)
+    }
+
+    /// Delivery log of a webhook, newest first.
+    #[graphql(guard = "RoleGuard::new(Resource::Webhook, Action::Read)")]
+    pub async fn webhook_deliveries(
+        &self,
+        ctx: &Context<'_>,
+        webhook_uuid: String,
+        limit: Option<u64>,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let conn = get_conn_from_context(ctx).await?;
+        let webhook_uuid = Uuid::from_str(&webhook_uuid)?;
+        let limit = limit.unwrap_or(DEFAULT_DELIVERIES);
+
+        let deliveries = WebhookDeliveriesRepository::find_by_webhook(conn, webhook_uuid, limit)
+            .await?
+            .into_iter()
+            .map(WebhookDelivery::try_from)
+            .collect::<Result<Vec<WebhookDelivery>, _>>()?;
+
+        Ok(deliveries)
+    }
+}
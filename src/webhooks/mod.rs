@@ -0,0 +1,9 @@
+mod deliveries;
+pub mod dispatcher;
+pub mod graphql;
+mod repository;
+mod signature;
+
+pub use deliveries::{Error as DeliveriesError, WebhookDeliveriesRepository};
+pub use repository::*;
+pub use signature::*;
@@ -0,0 +1,267 @@
+use crate::{
+    utils::{datetime::get_now, uuid::get_uuid_bytes},
+    webhooks::{DeliveriesError, WebhookDeliveriesRepository},
+};
+use entity::{
+    enums::WebhookEvent,
+    posts,
+    webhooks::{self, Entity as Webhook},
+};
+use reqwest::Url;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use serde_json::json;
+use snafu::prelude::*;
+use std::str::FromStr;
+use uuid::Uuid;
+
+pub struct NewWebhookInput {
+    pub url: String,
+    /// A random secret is generated when none is given.
+    pub secret: Option<String>,
+    pub events: Vec<WebhookEvent>,
+    pub created_by: Uuid,
+}
+
+pub struct UpdateWebhookInput {
+    pub uuid: Uuid,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub active: bool,
+}
+
+pub struct WebhooksRepository;
+
+impl WebhooksRepository {
+    pub async fn create(
+        conn: &DatabaseConnection,
+        input: NewWebhookInput,
+    ) -> Result<webhooks::Model> {
+        ensure!(!input.events.is_empty(), MissingEventsSnafu);
+        let url = parse_url(&input.url)?;
+
+        let webhook = webhooks::ActiveModel {
+            url: Set(url),
+            secret: Set(input.secret.unwrap_or_else(generate_secret)),
+            events: Set(join_events(&input.events)),
+            created_by: Set(input.created_by.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let result = Webhook::insert(webhook)
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let uuid = Uuid::from_bytes(get_uuid_bytes(&result.last_insert_id));
+        WebhooksRepository::find_by_id(conn, uuid)
+            .await?
+            .context(WebhookNotFoundSnafu { uuid })
+    }
+
+    pub async fn update(
+        conn: &DatabaseConnection,
+        input: UpdateWebhookInput,
+    ) -> Result<webhooks::Model> {
+        ensure!(!input.events.is_empty(), MissingEventsSnafu);
+        let url = parse_url(&input.url)?;
+
+        let webhook = WebhooksRepository::find_by_id(conn, input.uuid)
+            .await?
+            .context(WebhookNotFoundSnafu { uuid: input.uuid })?;
+
+        let mut webhook: webhooks::ActiveModel = webhook.into();
+        webhook.url = Set(url);
+        webhook.events = Set(join_events(&input.events));
+        webhook.active = Set(input.active);
+        webhook.updated_at = Set(get_now());
+
+        webhook.update(conn).await.context(QueryFailedSnafu)
+    }
+
+    pub async fn delete(conn: &DatabaseConnection, uuid: Uuid) -> Result<()> {
+        Webhook::delete_by_id(uuid.as_bytes().to_vec())
+            .exec(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(
+        conn: &DatabaseConnection,
+        uuid: Uuid,
+    ) -> Result<Option<webhooks::Model>> {
+        Webhook::find_by_id(uuid.as_bytes().to_vec())
+            .one(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    pub async fn find_all(conn: &DatabaseConnection) -> Result<Vec<webhooks::Model>> {
+        Webhook::find()
+            .order_by_asc(webhooks::Column::CreatedAt)
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)
+    }
+
+    /// Queues a delivery of the event for every active webhook subscribed to it, the dispatcher
+    /// sends them in the background so saving a post never waits on a remote server. Queued
+    /// within the transaction saving the post, nothing is sent for a change that was rolled back.
+    pub async fn emit<C>(conn: &C, event: WebhookEvent, post: &posts::Model) -> Result<()>
+    where
+        C: ConnectionTrait,
+    {
+        let webhooks = Webhook::find()
+            .filter(webhooks::Column::Active.eq(true))
+            .all(conn)
+            .await
+            .context(QueryFailedSnafu)?;
+
+        let subscribed: Vec<webhooks::Model> = webhooks
+            .into_iter()
+            .filter(|webhook| split_events(&webhook.events).contains(&event))
+            .collect();
+
+        if subscribed.is_empty() {
+            return Ok(());
+        }
+
+        let payload = json!({
+            "event": event.to_string(),
+            "createdAt": get_now().to_rfc3339(),
+            "post": {
+                "uuid": Uuid::from_bytes(post.uuid()).to_string(),
+                "slug": post.slug,
+                "title": post.title,
+                "status": post.status,
+                "publishedAt": post.published_at.map(|date| date.to_rfc3339()),
+            },
+        })
+        .to_string();
+
+        for webhook in subscribed {
+            WebhookDeliveriesRepository::enqueue(conn, &webhook, &event, &payload)
+                .await
+                .context(DeliveriesRepoFailedSnafu)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn split_events(events: &str) -> Vec<WebhookEvent> {
+    events
+        .split(',')
+        .filter_map(|event| WebhookEvent::from_str(event.trim()).ok())
+        .collect()
+}
+
+/// Deliveries are plain HTTP requests, nothing but an http(s) URL can receive them.
+fn parse_url(url: &str) -> Result<String> {
+    let url = url.trim();
+    let valid = Url::parse(url)
+        .map(|parsed| matches!(parsed.scheme(), "http" | "https") && parsed.has_host())
+        .unwrap_or(false);
+    ensure!(valid, InvalidUrlSnafu { url });
+
+    Ok(url.to_string())
+}
+
+fn join_events(events: &[WebhookEvent]) -> String {
+    events
+        .iter()
+        .map(|event| event.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn generate_secret() -> String {
+    format!(
+        "{}{}",
+        Uuid::new_v4().to_simple(),
+        Uuid::new_v4().to_simple()
+    )
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Webhooks Query failed: {}", source))]
+    QueryFailed { source: DbErr },
+
+    #[snafu(display("Webhook does not exist with uuid {}", uuid))]
+    WebhookNotFound { uuid: Uuid },
+
+    #[snafu(display("Webhook needs at least one event"))]
+    MissingEvents,
+
+    #[snafu(display("Webhook URL '{}' is not an http(s) URL", url))]
+    InvalidUrl { url: String },
+
+    #[snafu(display("Failed to queue the deliveries: {}", source))]
+    DeliveriesRepoFailed { source: DeliveriesError },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing;
+
+    #[test]
+    fn only_accepts_http_urls() {
+        assert_eq!(
+            parse_url(" https://example.com/hook ").unwrap(),
+            "https://example.com/hook"
+        );
+        assert!(parse_url("http://localhost:3000/hook").is_ok());
+
+        assert!(parse_url("ftp://example.com/hook").is_err());
+        assert!(parse_url("file:///etc/passwd").is_err());
+        assert!(parse_url("javascript:alert(1)").is_err());
+        assert!(parse_url("example.com/hook").is_err());
+        assert!(parse_url("").is_err());
+    }
+
+    #[rocket::async_test]
+    async fn rejects_other_urls_on_create_and_update() {
+        let conn = testing::connect().await;
+        let user = testing::create_user(&conn, "admin@example.com").await;
+
+        let input = NewWebhookInput {
+            url: String::from("ftp://example.com/hook"),
+            secret: None,
+            events: vec![WebhookEvent::PostCreated],
+            created_by: user,
+        };
+        let result = WebhooksRepository::create(&conn, input).await;
+        assert!(matches!(result, Err(Error::InvalidUrl { .. })));
+
+        let input = NewWebhookInput {
+            url: String::from("https://example.com/hook"),
+            secret: None,
+            events: vec![WebhookEvent::PostCreated],
+            created_by: user,
+        };
+        let webhook = WebhooksRepository::create(&conn, input).await.unwrap();
+
+        let input = UpdateWebhookInput {
+            uuid: Uuid::from_bytes(webhook.uuid()),
+            url: String::from("gopher://example.com/hook"),
+            events: vec![WebhookEvent::PostCreated],
+            active: true,
+        };
+        let result = WebhooksRepository::update(&conn, input).await;
+        assert!(matches!(result, Err(Error::InvalidUrl { .. })));
+
+        let stored = WebhooksRepository::find_by_id(&conn, Uuid::from_bytes(webhook.uuid()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.url, "https://example.com/hook");
+    }
+}
@@ -0,0 +1,35 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// HMAC-SHA256 of the body with the webhook secret, hex encoded and prefixed with the algorithm,
+/// e.g. `sha256=3b1f…`. Receivers compute the same over the raw body to verify it came from us.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body.as_bytes());
+
+    format!(
+        "sha256={}",
+        data_encoding::HEXLOWER.encode(&mac.finalize().into_bytes())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_the_body_with_the_secret() {
+        // Test case 2 of RFC 4231.
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            sign("whsec_test", r#"{"event":"PostCreated"}"#),
+            "sha256=4906e23eed3ada9d2f68eff4dbc7774d9e87f12d97be726c56fe1bbf45d0b6c0"
+        );
+    }
+}